@@ -0,0 +1,428 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    str::FromStr,
+    sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use crate::{GameError, GameState, MineField, Strategy, StrategyChain};
+
+/// size and number of mines of the boards to play
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoardConfig {
+    pub name: String,
+    pub width: usize,
+    pub height: usize,
+    pub mines: u32,
+}
+
+impl BoardConfig {
+    pub fn new(width: usize, height: usize, mines: u32) -> Self {
+        Self {
+            name: format!("{width}x{height}:{mines}"),
+            width,
+            height,
+            mines,
+        }
+    }
+
+    pub fn beginner() -> Self {
+        Self {
+            name: "beginner".into(),
+            ..Self::new(9, 9, 10)
+        }
+    }
+
+    pub fn intermediate() -> Self {
+        Self {
+            name: "intermediate".into(),
+            ..Self::new(16, 16, 40)
+        }
+    }
+
+    pub fn expert() -> Self {
+        Self {
+            name: "expert".into(),
+            ..Self::new(30, 16, 99)
+        }
+    }
+
+    /// board with a share `density` of its cells holding mines
+    pub fn with_density(width: usize, height: usize, density: f64) -> Self {
+        let mines = (density * (width * height) as f64) as u32;
+        Self {
+            name: format!("{width}x{height}@{density}"),
+            ..Self::new(width, height, mines)
+        }
+    }
+}
+
+/// `beginner`, `intermediate`, `expert`, `WxH:MINES` or `WxH@DENSITY`
+impl FromStr for BoardConfig {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "beginner" => return Ok(Self::beginner()),
+            "intermediate" => return Ok(Self::intermediate()),
+            "expert" => return Ok(Self::expert()),
+            _ => {}
+        }
+
+        let invalid = || format!("Invalid board configuration {s:?}");
+
+        let (size, mines, density) = if let Some((size, mines)) = s.split_once(':') {
+            (size, Some(mines), None)
+        } else if let Some((size, density)) = s.split_once('@') {
+            (size, None, Some(density))
+        } else {
+            return Err(invalid());
+        };
+
+        let (width, height) = size.split_once('x').ok_or_else(invalid)?;
+        let width = width.parse().map_err(|_| invalid())?;
+        let height = height.parse().map_err(|_| invalid())?;
+
+        match (mines, density) {
+            (Some(mines), _) => Ok(Self::new(
+                width,
+                height,
+                mines.parse().map_err(|_| invalid())?,
+            )),
+            (_, Some(density)) => Ok(Self::with_density(
+                width,
+                height,
+                density.parse().map_err(|_| invalid())?,
+            )),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// outcome of one game played by the solver
+#[derive(Clone, Debug, Default)]
+pub struct GameStats {
+    pub won: bool,
+    /// moves played by strategies which are not deterministic
+    pub guesses: usize,
+    pub duration: Duration,
+    /// number of moves played by each strategy
+    pub moves: BTreeMap<&'static str, usize>,
+}
+
+/// play one seeded game until it is over, or until the solver cannot find a move
+pub fn play_game(
+    config: &BoardConfig,
+    seed: u64,
+    solver: &StrategyChain,
+) -> Result<GameStats, GameError> {
+    let mut game = MineField::try_seeded(config.width, config.height, config.mines, seed)?;
+    let mut stats = GameStats::default();
+
+    let guessing: Vec<&'static str> = solver
+        .iter()
+        .filter(|strategy| !strategy.is_deterministic())
+        .map(|strategy| strategy.name())
+        .collect();
+
+    let start = Instant::now();
+
+    while let Some(next_move) = solver.next_move(&game.view()) {
+        let strategy = next_move.strategy.unwrap_or("unknown");
+        *stats.moves.entry(strategy).or_default() += 1;
+        if guessing.contains(&strategy) {
+            stats.guesses += 1;
+        }
+
+        match game.try_play(next_move) {
+            Ok(GameState::Ongoing) => {}
+            Ok(_) | Err(_) => break,
+        }
+    }
+
+    stats.duration = start.elapsed();
+    stats.won = game.state() == GameState::Won;
+
+    Ok(stats)
+}
+
+/// aggregated outcome of the games played on one board configuration
+#[derive(Clone, Debug)]
+pub struct BenchReport {
+    pub config: BoardConfig,
+    pub games: usize,
+    pub wins: usize,
+    pub guesses: usize,
+    pub duration: Duration,
+    pub moves: BTreeMap<&'static str, usize>,
+}
+
+/// a report of no game at all has a win rate of 0, within an interval of any win rate
+impl BenchReport {
+    pub fn win_rate(&self) -> f64 {
+        if self.games == 0 {
+            return 0.;
+        }
+        self.wins as f64 / self.games as f64
+    }
+
+    /// 95% Wilson score interval of the win rate
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        if self.games == 0 {
+            return (0., 1.);
+        }
+
+        let z: f64 = 1.96;
+        let n = self.games as f64;
+        let p = self.win_rate();
+
+        let denominator = 1. + z * z / n;
+        let centre = (p + z * z / (2. * n)) / denominator;
+        let margin = z * (p * (1. - p) / n + z * z / (4. * n * n)).sqrt() / denominator;
+
+        ((centre - margin).max(0.), (centre + margin).min(1.))
+    }
+
+    pub fn average_guesses(&self) -> f64 {
+        if self.games == 0 {
+            return 0.;
+        }
+        self.guesses as f64 / self.games as f64
+    }
+
+    pub fn mean_duration(&self) -> Duration {
+        self.duration
+            .checked_div(self.games as u32)
+            .unwrap_or(Duration::ZERO)
+    }
+}
+
+/// play `games` games on `config` across `threads` threads, the game `i` being seeded with `seed + i`
+/// each thread builds its own solver with `solver`
+pub fn run(
+    config: &BoardConfig,
+    games: usize,
+    seed: u64,
+    threads: usize,
+    solver: impl Fn() -> StrategyChain + Sync,
+) -> Result<BenchReport, GameError> {
+    let next_game = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(games));
+
+    std::thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            scope.spawn(|| {
+                let solver = solver();
+                loop {
+                    let index = next_game.fetch_add(1, Ordering::Relaxed);
+                    if index >= games {
+                        break;
+                    }
+
+                    let stats = play_game(config, seed.wrapping_add(index as u64), &solver);
+                    results.lock().unwrap().push(stats);
+                }
+            });
+        }
+    });
+
+    let mut report = BenchReport {
+        config: config.clone(),
+        games,
+        wins: 0,
+        guesses: 0,
+        duration: Duration::ZERO,
+        moves: BTreeMap::new(),
+    };
+
+    for stats in results.into_inner().unwrap() {
+        let stats = stats?;
+        report.wins += stats.won as usize;
+        report.guesses += stats.guesses;
+        report.duration += stats.duration;
+        for (strategy, moves) in stats.moves {
+            *report.moves.entry(strategy).or_default() += moves;
+        }
+    }
+
+    Ok(report)
+}
+
+/// every strategy which played at least once in one of the reports
+fn strategies(reports: &[BenchReport]) -> Vec<&'static str> {
+    let mut strategies: Vec<&'static str> = reports
+        .iter()
+        .flat_map(|report| report.moves.keys().copied())
+        .collect();
+    strategies.sort();
+    strategies.dedup();
+    strategies
+}
+
+/// human readable table, one line per board configuration
+pub struct Table<'a>(pub &'a [BenchReport]);
+
+impl Display for Table<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let strategies = strategies(self.0);
+
+        write!(
+            f,
+            "{:<16} {:>7} {:>8} {:>17} {:>8} {:>10}",
+            "board", "games", "win rate", "95% ci", "guesses", "time (ms)"
+        )?;
+        for strategy in &strategies {
+            write!(f, " {strategy:>13}")?;
+        }
+        writeln!(f)?;
+
+        for report in self.0 {
+            let (low, high) = report.confidence_interval();
+            write!(
+                f,
+                "{:<16} {:>7} {:>7.1}% {:>7.1}% - {:>5.1}% {:>8.2} {:>10.3}",
+                report.config.name,
+                report.games,
+                report.win_rate() * 100.,
+                low * 100.,
+                high * 100.,
+                report.average_guesses(),
+                report.mean_duration().as_secs_f64() * 1000.
+            )?;
+            for strategy in &strategies {
+                write!(f, " {:>13}", report.moves.get(strategy).unwrap_or(&0))?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// comma separated values with a header line, moves of each strategy in `moves_<strategy>` columns
+pub struct Csv<'a>(pub &'a [BenchReport]);
+
+impl Display for Csv<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let strategies = strategies(self.0);
+
+        f.write_str("board,width,height,mines,games,wins,win_rate,ci_low,ci_high,average_guesses,mean_time_ms")?;
+        for strategy in &strategies {
+            write!(f, ",moves_{strategy}")?;
+        }
+        writeln!(f)?;
+
+        for report in self.0 {
+            let (low, high) = report.confidence_interval();
+            write!(
+                f,
+                "{},{},{},{},{},{},{},{},{},{},{}",
+                report.config.name,
+                report.config.width,
+                report.config.height,
+                report.config.mines,
+                report.games,
+                report.wins,
+                report.win_rate(),
+                low,
+                high,
+                report.average_guesses(),
+                report.mean_duration().as_secs_f64() * 1000.
+            )?;
+            for strategy in &strategies {
+                write!(f, ",{}", report.moves.get(strategy).unwrap_or(&0))?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// array of one JSON object per board configuration
+pub struct Json<'a>(pub &'a [BenchReport]);
+
+impl Display for Json<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("[")?;
+
+        for (index, report) in self.0.iter().enumerate() {
+            if index > 0 {
+                f.write_str(",")?;
+            }
+
+            let (low, high) = report.confidence_interval();
+            write!(
+                f,
+                "\n  {{\"board\": {:?}, \"width\": {}, \"height\": {}, \"mines\": {}, \"games\": {}, \"wins\": {}, \
+                 \"win_rate\": {}, \"ci_low\": {}, \"ci_high\": {}, \"average_guesses\": {}, \"mean_time_ms\": {}, \
+                 \"moves\": {{",
+                report.config.name,
+                report.config.width,
+                report.config.height,
+                report.config.mines,
+                report.games,
+                report.wins,
+                report.win_rate(),
+                low,
+                high,
+                report.average_guesses(),
+                report.mean_duration().as_secs_f64() * 1000.
+            )?;
+            for (index, (strategy, moves)) in report.moves.iter().enumerate() {
+                if index > 0 {
+                    f.write_str(", ")?;
+                }
+                write!(f, "{strategy:?}: {moves}")?;
+            }
+            f.write_str("}}")?;
+        }
+
+        f.write_str("\n]\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_board_config_parsing() {
+        assert_eq!("expert".parse(), Ok(BoardConfig::expert()));
+        assert_eq!("8x6:12".parse(), Ok(BoardConfig::new(8, 6, 12)));
+        assert_eq!(
+            "10x10@0.2"
+                .parse::<BoardConfig>()
+                .map(|config| config.mines),
+            Ok(20)
+        );
+        assert!("10x10".parse::<BoardConfig>().is_err());
+    }
+
+    #[test]
+    fn test_run_is_reproducible() {
+        let config = BoardConfig::beginner();
+
+        let first = run(&config, 20, 7, 4, StrategyChain::standard).unwrap();
+        let second = run(&config, 20, 7, 1, StrategyChain::standard).unwrap();
+
+        assert_eq!(first.games, 20);
+        assert_eq!(first.moves.get("immediate"), second.moves.get("immediate"));
+        let (low, high) = first.confidence_interval();
+        assert!(low <= first.win_rate() && first.win_rate() <= high);
+    }
+
+    #[test]
+    fn test_run_without_games() {
+        let report = run(&BoardConfig::beginner(), 0, 7, 2, StrategyChain::standard).unwrap();
+
+        assert_eq!(report.mean_duration(), Duration::ZERO);
+        assert_eq!(report.win_rate(), 0.);
+        assert_eq!(report.average_guesses(), 0.);
+        assert_eq!(report.confidence_interval(), (0., 1.));
+    }
+}
@@ -0,0 +1,150 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::Position;
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Action {
+    Open(Position),
+    /// flag a closed cell, leaving flagged cells as they are
+    Flag(Position),
+    /// remove the flag of a flagged cell
+    Unflag(Position),
+    /// flag a closed cell, or remove the flag of a flagged one
+    ToggleFlag(Position),
+    /// mark a closed cell as uncertain, or remove that mark
+    QuestionMark(Position),
+    /// open every closed neighbour of an open cell whose mines are all flagged
+    Chord(Position),
+    /// actions played in order, stopping as soon as the game is over
+    Batch(Vec<Action>),
+}
+
+impl Action {
+    /// cell the action is played on, `None` for a batch
+    pub fn position(&self) -> Option<Position> {
+        match self {
+            Action::Open(pos)
+            | Action::Flag(pos)
+            | Action::Unflag(pos)
+            | Action::ToggleFlag(pos)
+            | Action::QuestionMark(pos)
+            | Action::Chord(pos) => Some(*pos),
+            Action::Batch(_) => None,
+        }
+    }
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Action::Open(pos) => write!(f, "open {pos}"),
+            Action::Flag(pos) => write!(f, "flag {pos}"),
+            Action::Unflag(pos) => write!(f, "unflag {pos}"),
+            Action::ToggleFlag(pos) => write!(f, "toggle flag {pos}"),
+            Action::QuestionMark(pos) => write!(f, "question mark {pos}"),
+            Action::Chord(pos) => write!(f, "chord {pos}"),
+            Action::Batch(actions) => {
+                for (index, action) in actions.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(", ")?;
+                    }
+                    action.fmt(f)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// reads back what [`Action`] displays, e.g. `open (1, 2)`, `open (1, 2, 3)` or `flag (0, 0), chord (1, 1)`
+impl FromStr for Action {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let actions: Vec<&str> = s.split_inclusive("), ").collect();
+        if actions.len() > 1 {
+            return actions
+                .into_iter()
+                .map(|action| action.trim_end_matches(", ").parse())
+                .collect::<Result<_, _>>()
+                .map(Action::Batch);
+        }
+
+        let invalid = || format!("Invalid action {s:?}");
+
+        let (name, pos) = s.trim().split_once(" (").ok_or_else(invalid)?;
+        let coordinates = pos
+            .strip_suffix(')')
+            .ok_or_else(invalid)?
+            .split(", ")
+            .map(|coordinate| coordinate.parse().map_err(|_| invalid()))
+            .collect::<Result<Vec<usize>, _>>()?;
+        let pos = match coordinates[..] {
+            [x, y] => Position::from(x, y),
+            [x, y, z] => (x, y, z).into(),
+            _ => return Err(invalid()),
+        };
+
+        match name {
+            "open" => Ok(Action::Open(pos)),
+            "flag" => Ok(Action::Flag(pos)),
+            "unflag" => Ok(Action::Unflag(pos)),
+            "toggle flag" => Ok(Action::ToggleFlag(pos)),
+            "question mark" => Ok(Action::QuestionMark(pos)),
+            "chord" => Ok(Action::Chord(pos)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// an action, along with why it was played and by whom
+#[derive(Clone, Debug, PartialEq)]
+pub struct Move {
+    pub action: Action,
+    pub reason: Option<String>,
+    /// name of the strategy which found the move
+    pub strategy: Option<&'static str>,
+}
+
+impl Move {
+    pub fn new(action: Action) -> Self {
+        Self {
+            action,
+            reason: None,
+            strategy: None,
+        }
+    }
+
+    pub fn with_reason(mut self, reason: impl Into<String>) -> Self {
+        self.reason = Some(reason.into());
+        self
+    }
+
+    pub fn with_strategy(mut self, strategy: &'static str) -> Self {
+        self.strategy = Some(strategy);
+        self
+    }
+}
+
+impl From<Action> for Move {
+    fn from(action: Action) -> Self {
+        Self::new(action)
+    }
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(strategy) = self.strategy {
+            write!(f, "[{strategy}] ")?;
+        }
+
+        self.action.fmt(f)?;
+
+        if let Some(reason) = &self.reason {
+            write!(f, ": {reason}")?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,8 +1,10 @@
-#[derive(Clone, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(super) enum CellState {
     Closed,
     Open,
     Flagged,
+    Questioned,
 }
 
 #[derive(Clone)]
@@ -20,11 +22,14 @@ impl Default for Cell {
     }
 }
 
-#[derive(PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CellData {
     Closed,
     Open(usize),
     Flagged,
+    /// closed, and marked as uncertain by the player
+    Questioned,
 }
 
 impl CellData {
@@ -36,12 +41,9 @@ impl CellData {
         }
     }
 
+    /// whether the cell is closed, question marks being mere annotations on closed cells
     pub fn is_closed(self) -> bool {
-        if let CellData::Closed = self {
-            true
-        } else {
-            false
-        }
+        matches!(self, CellData::Closed | CellData::Questioned)
     }
 }
 
@@ -0,0 +1,107 @@
+use std::fmt::Display;
+
+use crate::{GameState, Position};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum GameError {
+    /// the board has no cell at all
+    EmptyBoard,
+    /// the board has more cells than fit in memory
+    BoardTooLarge,
+    /// the board has no cell left without a mine
+    TooManyMines {
+        mines: usize,
+        cells: usize,
+    },
+    OutOfBounds(Position),
+    AlreadyOpen(Position),
+    /// opening a flagged cell, which must be unflagged first
+    Flagged(Position),
+    /// chording on a cell which is not open
+    NotOpen(Position),
+    GameOver(GameState),
+    /// a board read from text holds a character standing for no cell
+    InvalidCharacter {
+        character: char,
+        pos: Position,
+    },
+    /// a board read from text has an open cell showing a number other than its neighbouring mines
+    WrongCount {
+        pos: Position,
+        shown: usize,
+        mines: usize,
+    },
+    /// a row of a board read from text, given by the position of its first cell, has a number of characters other
+    /// than the first one
+    RowWidth {
+        row: Position,
+        characters: usize,
+        expected: usize,
+    },
+    /// a layer of a board read from text has a number of rows other than the first one
+    LayerHeight {
+        layer: usize,
+        rows: usize,
+        expected: usize,
+    },
+    /// a view of a board with a number of cells other than the board has
+    CellCount {
+        cells: usize,
+        expected: usize,
+    },
+    /// a saved game which no game could have led to
+    InvalidSave(String),
+    /// none of the boards generated within the attempt budget could be solved without guessing
+    NoGuessBoardNotFound {
+        attempts: usize,
+    },
+}
+
+impl Display for GameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameError::EmptyBoard => f.write_str("the board has no cell"),
+            GameError::BoardTooLarge => f.write_str("the board has too many cells"),
+            GameError::TooManyMines { mines, cells } => write!(
+                f,
+                "{mines} mines leave no free cell on a board of {cells} cells"
+            ),
+            GameError::OutOfBounds(pos) => write!(f, "{pos} is out of the board"),
+            GameError::AlreadyOpen(pos) => write!(f, "{pos} is already open"),
+            GameError::Flagged(pos) => write!(f, "{pos} is flagged"),
+            GameError::NotOpen(pos) => write!(f, "{pos} is not open"),
+            GameError::GameOver(GameState::Won) => f.write_str("the game is already won"),
+            GameError::GameOver(GameState::Lost) => f.write_str("the game is already lost"),
+            GameError::GameOver(GameState::Ongoing) => f.write_str("the game is not over"),
+            GameError::InvalidCharacter { character, pos } => {
+                write!(f, "{character:?} at {pos} is not a cell")
+            }
+            GameError::WrongCount { pos, shown, mines } => {
+                write!(f, "{pos} shows {shown} but has {mines} neighbouring mines")
+            }
+            GameError::RowWidth {
+                row,
+                characters,
+                expected,
+            } => write!(
+                f,
+                "the row at {row} has {characters} characters instead of {expected}"
+            ),
+            GameError::LayerHeight {
+                layer,
+                rows,
+                expected,
+            } => write!(f, "layer {layer} has {rows} rows instead of {expected}"),
+            GameError::CellCount { cells, expected } => {
+                write!(f, "{cells} cells given for a board of {expected} cells")
+            }
+            GameError::InvalidSave(reason) => write!(f, "invalid saved game: {reason}"),
+            GameError::NoGuessBoardNotFound { attempts } => write!(
+                f,
+                "no board solvable without guessing was found in {attempts} attempts"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GameError {}
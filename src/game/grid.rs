@@ -0,0 +1,457 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::{Kernel, Position};
+
+/// shape of a board: which positions it has, and which of them neighbour each other
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Grid {
+    width: usize,
+    height: usize,
+    /// number of layers, 1 for a flat board
+    #[cfg_attr(feature = "serde", serde(default = "flat"))]
+    depth: usize,
+    #[cfg_attr(feature = "serde", serde(default))]
+    topology: Topology,
+    #[cfg_attr(feature = "serde", serde(default))]
+    tiling: Tiling,
+    /// neighbours of a cell in place of those of its tiling
+    #[cfg_attr(feature = "serde", serde(default))]
+    kernel: Option<Kernel>,
+    /// whether each position of the rectangle is a cell of the board, `None` when all of them are
+    #[cfg_attr(feature = "serde", serde(default))]
+    mask: Option<Vec<bool>>,
+}
+
+impl Grid {
+    /// a flat and bounded rectangle of square cells, see [`Grid::with_depth`], [`Grid::with_topology`],
+    /// [`Grid::with_tiling`], [`Grid::with_kernel`] and [`Grid::with_mask`]
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            depth: 1,
+            topology: Topology::Bounded,
+            tiling: Tiling::Square,
+            kernel: None,
+            mask: None,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// number of rows of each layer
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// the same board, with `depth` layers stacked on top of each other, each cell neighbouring the cells around it
+    /// in the layers below and above as well
+    pub fn with_depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// number of positions of the board, masked ones included, as many as a vector of all its cells holds
+    pub fn len(&self) -> usize {
+        self.width * self.height * self.depth
+    }
+
+    /// [`Grid::len`], `None` when it overflows
+    pub fn checked_len(&self) -> Option<usize> {
+        self.width.checked_mul(self.height)?.checked_mul(self.depth)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// number of cells of the board, masked positions left out
+    pub fn cell_count(&self) -> usize {
+        match &self.mask {
+            Some(mask) => mask.iter().filter(|playable| **playable).count(),
+            None => self.len(),
+        }
+    }
+
+    /// whether `pos` is a cell of the board, neither out of it nor masked
+    pub fn contains(&self, pos: Position) -> bool {
+        self.bounds(pos) && !self.is_masked(pos)
+    }
+
+    /// whether `pos` is within the rectangle of the board but no cell of it
+    pub fn is_masked(&self, pos: Position) -> bool {
+        self.bounds(pos)
+            && self
+                .mask
+                .as_ref()
+                .is_some_and(|mask| !mask[self.index(pos)])
+    }
+
+    fn bounds(&self, pos: Position) -> bool {
+        pos.x() < self.width && pos.y() < self.height && pos.z() < self.depth
+    }
+
+    /// the same board, without the positions for which `playable` is false: they hold no mine, neighbour no cell and
+    /// are left out of [`Grid::positions`]
+    pub fn with_mask(mut self, playable: impl Fn(Position) -> bool) -> Self {
+        let mask: Vec<bool> = self
+            .all_positions()
+            .map(|pos| self.contains(pos) && playable(pos))
+            .collect();
+        self.mask = mask.contains(&false).then_some(mask);
+        self
+    }
+
+    /// index of a position of the board in a vector of all its cells, layer by layer and row by row
+    pub fn index(&self, pos: Position) -> usize {
+        pos.x() + self.width * (pos.y() + self.height * pos.z())
+    }
+
+    /// position of the cell at `index` in a vector of all the cells of the board
+    pub fn position(&self, index: usize) -> Position {
+        let (row, x) = (index / self.width, index % self.width);
+        (x, row % self.height, row / self.height).into()
+    }
+
+    /// every cell of the board, layer by layer and row by row, masked positions left out
+    pub fn positions(&self) -> impl Iterator<Item = Position> + '_ {
+        self.all_positions().filter(|pos| !self.is_masked(*pos))
+    }
+
+    /// every position of the board, masked ones included, in the order of their indices
+    pub fn all_positions(&self) -> impl Iterator<Item = Position> + '_ {
+        (0..self.len()).map(|index| self.position(index))
+    }
+
+    /// the cell closest to the middle of the board
+    pub fn centre(&self) -> Option<Position> {
+        let middle = (self.width / 2, self.height / 2, self.depth / 2);
+        self.positions().min_by_key(|pos| {
+            let distance = |a: usize, b: usize| a.abs_diff(b).pow(2);
+            distance(pos.x(), middle.0) + distance(pos.y(), middle.1) + distance(pos.z(), middle.2)
+        })
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// the same board, its edges joined as `topology` tells
+    pub fn with_topology(mut self, topology: Topology) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    pub fn tiling(&self) -> Tiling {
+        self.tiling
+    }
+
+    /// the same board, made of cells of another shape
+    pub fn with_tiling(mut self, tiling: Tiling) -> Self {
+        self.tiling = tiling;
+        self
+    }
+
+    /// offsets to the neighbours of a cell, those of its tiling unless the board was given a kernel
+    pub fn kernel(&self) -> Kernel {
+        self.kernel.unwrap_or_else(|| self.tiling.kernel())
+    }
+
+    /// the same board, each cell neighbouring the cells `kernel` picks around it instead of the cells touching it
+    pub fn with_kernel(mut self, kernel: Kernel) -> Self {
+        self.kernel = Some(kernel);
+        self
+    }
+
+    /// positions around `pos`, layer by layer and row by row, those across a joined edge included and masked ones
+    /// left out
+    ///
+    /// in the layers below and above, the neighbours are the cell right under or over `pos` and the ones around it
+    pub fn neighbours(&self, pos: Position) -> Vec<Position> {
+        let kernel = self.kernel();
+        let mut neighbours = Vec::with_capacity(3 * kernel.len() + 2);
+
+        for dz in [-1, 0, 1] {
+            let Some(z) = step(pos.z(), dz, self.depth, false) else {
+                continue;
+            };
+            let centre = (dz != 0).then_some((0, 0));
+
+            for (dx, dy) in kernel.offsets().chain(centre) {
+                let (Some(x), Some(y)) = (
+                    step(pos.x(), dx, self.width, self.topology.wraps_horizontally()),
+                    step(pos.y(), dy, self.height, self.topology.wraps_vertically()),
+                ) else {
+                    continue;
+                };
+
+                // on a board two cells wide, the cells on both sides across the joined edge are the same one
+                let neighbour = (x, y, z).into();
+                if neighbour != pos
+                    && !self.is_masked(neighbour)
+                    && !neighbours.contains(&neighbour)
+                {
+                    neighbours.push(neighbour);
+                }
+            }
+        }
+
+        neighbours
+    }
+}
+
+#[cfg(feature = "serde")]
+fn flat() -> usize {
+    1
+}
+
+/// coordinate `delta` away from `coordinate` along an axis of `length` cells, going round when `wraps`
+fn step(coordinate: usize, delta: isize, length: usize, wraps: bool) -> Option<usize> {
+    match coordinate.checked_add_signed(delta) {
+        Some(moved) if moved < length => Some(moved),
+        _ if wraps => Some((coordinate + length).wrapping_add_signed(delta) % length),
+        _ => None,
+    }
+}
+
+/// shape of the cells of a board
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Tiling {
+    /// squares, each with eight neighbours
+    #[default]
+    Square,
+    /// hexagons in axial coordinates, each with six neighbours: `x` goes along a row, and each row is shifted half a
+    /// cell to the right of the one above, so that the board is a rhombus
+    Hexagonal,
+}
+
+impl Tiling {
+    /// offsets from a cell to the cells touching it
+    pub fn kernel(self) -> Kernel {
+        match self {
+            Tiling::Square => Kernel::ring(),
+            Tiling::Hexagonal => Kernel::hexagonal(),
+        }
+    }
+}
+
+/// `square` or `hexagonal`
+impl FromStr for Tiling {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "square" => Ok(Tiling::Square),
+            "hexagonal" => Ok(Tiling::Hexagonal),
+            _ => Err(format!("Unknown tiling {s:?}")),
+        }
+    }
+}
+
+impl Display for Tiling {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Tiling::Square => "square",
+            Tiling::Hexagonal => "hexagonal",
+        })
+    }
+}
+
+/// which edges of a board are joined to the opposite one, so that the cells along them neighbour each other
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Topology {
+    /// a plain rectangle, whose edge cells have fewer neighbours
+    #[default]
+    Bounded,
+    /// the left and right edges are joined, like around a cylinder
+    WrapHorizontally,
+    /// the top and bottom edges are joined
+    WrapVertically,
+    /// both pairs of edges are joined, leaving no edge at all
+    Torus,
+}
+
+impl Topology {
+    pub fn wraps_horizontally(self) -> bool {
+        matches!(self, Topology::WrapHorizontally | Topology::Torus)
+    }
+
+    pub fn wraps_vertically(self) -> bool {
+        matches!(self, Topology::WrapVertically | Topology::Torus)
+    }
+}
+
+/// `bounded`, `wrap-horizontally`, `wrap-vertically` or `torus`
+impl FromStr for Topology {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bounded" => Ok(Topology::Bounded),
+            "wrap-horizontally" => Ok(Topology::WrapHorizontally),
+            "wrap-vertically" => Ok(Topology::WrapVertically),
+            "torus" => Ok(Topology::Torus),
+            _ => Err(format!("Unknown topology {s:?}")),
+        }
+    }
+}
+
+impl Display for Topology {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Topology::Bounded => "bounded",
+            Topology::WrapHorizontally => "wrap-horizontally",
+            Topology::WrapVertically => "wrap-vertically",
+            Topology::Torus => "torus",
+        })
+    }
+}
+
+impl AsRef<Grid> for Grid {
+    fn as_ref(&self) -> &Grid {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_neighbours_across_joined_edges() {
+        let grid = Grid::new(4, 3);
+        assert_eq!(
+            grid.neighbours((0, 0).into()),
+            [(1, 0).into(), (0, 1).into(), (1, 1).into()] as [Position; 3]
+        );
+
+        let torus = grid.clone().with_topology(Topology::Torus);
+        let neighbours = torus.neighbours((0, 0).into());
+        assert_eq!(neighbours.len(), 8);
+        assert!(neighbours.contains(&(3, 2).into()));
+        assert!(neighbours.contains(&(3, 0).into()));
+
+        let cylinder = grid.with_topology(Topology::WrapHorizontally);
+        assert_eq!(
+            cylinder.neighbours((3, 0).into()),
+            [
+                (2, 0).into(),
+                (0, 0).into(),
+                (2, 1).into(),
+                (3, 1).into(),
+                (0, 1).into()
+            ] as [Position; 5]
+        );
+
+        // the cells on both sides of a narrow board are the same, and a cell is never its own neighbour
+        let narrow = Grid::new(2, 1).with_topology(Topology::Torus);
+        assert_eq!(
+            narrow.neighbours((0, 0).into()),
+            [(1, 0).into()] as [Position; 1]
+        );
+    }
+
+    #[test]
+    fn test_hexagonal_neighbours() {
+        let grid = Grid::new(4, 3).with_tiling(Tiling::Hexagonal);
+        assert_eq!(
+            grid.neighbours((1, 1).into()),
+            [
+                (1, 0).into(),
+                (2, 0).into(),
+                (0, 1).into(),
+                (2, 1).into(),
+                (0, 2).into(),
+                (1, 2).into()
+            ] as [Position; 6]
+        );
+        assert_eq!(
+            grid.neighbours((0, 0).into()),
+            [(1, 0).into(), (0, 1).into()] as [Position; 2]
+        );
+        assert_eq!(
+            grid.with_topology(Topology::Torus)
+                .neighbours((0, 0).into())
+                .len(),
+            6
+        );
+    }
+
+    #[test]
+    fn test_neighbours_of_a_kernel() {
+        let grid = Grid::new(5, 5).with_kernel(Kernel::knight());
+        assert_eq!(
+            grid.neighbours((0, 0).into()),
+            [(2, 1).into(), (1, 2).into()] as [Position; 2]
+        );
+        assert_eq!(grid.neighbours((2, 2).into()).len(), 8);
+
+        let cross = Grid::new(5, 5)
+            .with_kernel(Kernel::cross())
+            .with_topology(Topology::Torus);
+        assert_eq!(
+            cross.neighbours((0, 0).into()),
+            [(0, 4).into(), (4, 0).into(), (1, 0).into(), (0, 1).into()] as [Position; 4]
+        );
+
+        // the kernel applies in the layers below and above, along with the cell right under or over
+        let layers = Grid::new(5, 5).with_depth(2).with_kernel(Kernel::cross());
+        assert_eq!(layers.neighbours((2, 2, 0).into()).len(), 9);
+        assert_eq!(
+            Grid::new(5, 5)
+                .with_kernel(Kernel::radius(2))
+                .neighbours((2, 2).into())
+                .len(),
+            24
+        );
+    }
+
+    #[test]
+    fn test_masked_positions() {
+        // a ring, the middle of the board being a hole
+        let grid = Grid::new(3, 3).with_mask(|pos| pos != (1, 1).into());
+        assert_eq!(grid.len(), 9);
+        assert_eq!(grid.cell_count(), 8);
+        assert!(grid.is_masked((1, 1).into()));
+        assert!(!grid.contains((1, 1).into()));
+        assert!(!grid.is_masked((3, 1).into()));
+        assert_eq!(grid.positions().count(), 8);
+        assert_eq!(grid.all_positions().count(), 9);
+        assert_eq!(
+            grid.neighbours((0, 0).into()),
+            [(1, 0).into(), (0, 1).into()] as [Position; 2]
+        );
+        assert_eq!(grid.centre(), Some((1, 0).into()));
+
+        // masking every hole of a board again leaves it as it was
+        assert_eq!(Grid::new(3, 3).with_mask(|_| true), Grid::new(3, 3));
+        assert_eq!(grid.clone().with_mask(|pos| pos.x() > 0).cell_count(), 5);
+    }
+
+    #[test]
+    fn test_neighbours_across_layers() {
+        let grid = Grid::new(3, 3).with_depth(3);
+        assert_eq!(grid.len(), 27);
+        assert_eq!(grid.neighbours((1, 1, 1).into()).len(), 26);
+        assert_eq!(grid.neighbours((0, 0, 0).into()).len(), 7);
+
+        let neighbours = grid.neighbours((1, 1, 0).into());
+        assert_eq!(neighbours.len(), 17);
+        assert!(neighbours.contains(&(1, 1, 1).into()));
+        assert!(!neighbours.contains(&(1, 1, 2).into()));
+
+        for (index, pos) in grid.positions().enumerate() {
+            assert_eq!(grid.index(pos), index);
+        }
+        assert_eq!(grid.position(13), (1, 1, 1).into());
+    }
+}
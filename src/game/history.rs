@@ -0,0 +1,36 @@
+use crate::{Action, GameState, Position, game::cell::CellState};
+
+/// a cell whose state an action changed
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(super) struct CellChange {
+    pub(super) pos: Position,
+    pub(super) before: CellState,
+    pub(super) after: CellState,
+}
+
+/// one action played on a field, and everything it changed so that it can be taken back
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HistoryEntry {
+    pub action: Action,
+    /// state of the game once the action was played
+    pub state: GameState,
+    pub(super) previous_state: GameState,
+    /// whether the first click was still to be played, before and after the action
+    pub(super) started: (bool, bool),
+    /// mine moved away from the first click, and where it went
+    pub(super) relocation: Option<(Position, Position)>,
+    pub(super) changes: Vec<CellChange>,
+}
+
+impl HistoryEntry {
+    /// cells the action opened, including those opened by the flood fill around empty cells
+    pub fn revealed(&self) -> Vec<Position> {
+        self.changes
+            .iter()
+            .filter(|change| change.after == CellState::Open)
+            .map(|change| change.pos)
+            .collect()
+    }
+}
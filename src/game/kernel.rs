@@ -0,0 +1,198 @@
+use std::{fmt::Display, str::FromStr};
+
+/// furthest a neighbour may be from a cell along either axis
+const REACH: isize = 3;
+/// side of the square of offsets a kernel picks from
+const SIDE: isize = 2 * REACH + 1;
+
+/// offsets from a cell to the cells it counts as neighbours, within the layer of the cell
+///
+/// a kernel is symmetric, so that every cell is a neighbour of its neighbours, and reaches at most three cells away
+/// along each axis
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kernel {
+    /// one bit per offset of the square around the cell, row by row
+    offsets: u64,
+}
+
+impl Kernel {
+    /// `None` when an offset is `(0, 0)` or out of reach, or when the mirror of an offset is missing
+    pub fn from_offsets(offsets: impl IntoIterator<Item = (isize, isize)>) -> Option<Self> {
+        let mut kernel = Self { offsets: 0 };
+        for (dx, dy) in offsets {
+            if (dx, dy) == (0, 0) || dx.abs() > REACH || dy.abs() > REACH {
+                return None;
+            }
+            kernel.offsets |= bit(dx, dy);
+        }
+
+        kernel
+            .offsets()
+            .all(|(dx, dy)| kernel.offsets & bit(-dx, -dy) != 0)
+            .then_some(kernel)
+    }
+
+    /// the eight cells around a cell, as on a classic board
+    pub fn ring() -> Self {
+        Self::radius(1)
+    }
+
+    /// the four cells sharing a side with a cell
+    pub fn cross() -> Self {
+        Self::square(1, |dx, dy| dx == 0 || dy == 0)
+    }
+
+    /// the eight cells a knight of chess can jump to
+    pub fn knight() -> Self {
+        Self::square(2, |dx, dy| dx.abs() + dy.abs() == 3)
+    }
+
+    /// every cell at most `radius` cells away along each axis
+    ///
+    /// # Panics
+    /// when `radius` is 0 or more than 3
+    pub fn radius(radius: usize) -> Self {
+        assert!(
+            (1..=REACH as usize).contains(&radius),
+            "A kernel reaches between 1 and {REACH} cells away"
+        );
+        Self::square(radius as isize, |_, _| true)
+    }
+
+    /// the six cells around a hexagon in axial coordinates, see [`crate::Tiling::Hexagonal`]
+    pub fn hexagonal() -> Self {
+        Self::square(1, |dx, dy| dx != dy)
+    }
+
+    /// offsets at most `radius` cells away along each axis for which `keep` holds
+    fn square(radius: isize, keep: impl Fn(isize, isize) -> bool) -> Self {
+        let mut offsets = 0;
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                if (dx, dy) != (0, 0) && keep(dx, dy) {
+                    offsets |= bit(dx, dy);
+                }
+            }
+        }
+        Self { offsets }
+    }
+
+    /// `(dx, dy)` from a cell to each of its neighbours, row by row
+    pub fn offsets(self) -> impl Iterator<Item = (isize, isize)> {
+        (0..SIDE * SIDE)
+            .filter(move |index| self.offsets & (1 << index) != 0)
+            .map(|index| (index % SIDE - REACH, index / SIDE - REACH))
+    }
+
+    /// number of neighbours of a cell away from any edge of a flat board
+    pub fn len(self) -> usize {
+        self.offsets.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.offsets == 0
+    }
+}
+
+fn bit(dx: isize, dy: isize) -> u64 {
+    1 << ((dy + REACH) * SIDE + dx + REACH)
+}
+
+/// `ring`, `cross`, `knight`, `radius-2`, `radius-3`, or offsets such as `1,0 -1,0` separated by spaces
+impl FromStr for Kernel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ring" | "radius-1" => return Ok(Self::ring()),
+            "cross" => return Ok(Self::cross()),
+            "knight" => return Ok(Self::knight()),
+            "radius-2" => return Ok(Self::radius(2)),
+            "radius-3" => return Ok(Self::radius(3)),
+            _ => {}
+        }
+
+        let offsets = s
+            .split_whitespace()
+            .map(|offset| {
+                let (dx, dy) = offset
+                    .split_once(',')
+                    .ok_or_else(|| format!("Invalid offset {offset:?}"))?;
+                let parse = |delta: &str| {
+                    delta
+                        .parse::<isize>()
+                        .map_err(|error| format!("Invalid offset {offset:?}: {error}"))
+                };
+                Ok((parse(dx)?, parse(dy)?))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        if offsets.is_empty() {
+            return Err(format!("Unknown kernel {s:?}"));
+        }
+
+        Self::from_offsets(offsets).ok_or_else(|| {
+            format!("Kernel {s:?} must be symmetric, without (0, 0) and at most {REACH} cells away")
+        })
+    }
+}
+
+impl Display for Kernel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let named = [
+            ("ring", Self::ring()),
+            ("cross", Self::cross()),
+            ("knight", Self::knight()),
+            ("radius-2", Self::radius(2)),
+            ("radius-3", Self::radius(3)),
+        ];
+        if let Some((name, _)) = named.iter().find(|(_, kernel)| kernel == self) {
+            return f.write_str(name);
+        }
+
+        let offsets: Vec<String> = self
+            .offsets()
+            .map(|(dx, dy)| format!("{dx},{dy}"))
+            .collect();
+        f.write_str(&offsets.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_named_kernels() {
+        assert_eq!(Kernel::ring().len(), 8);
+        assert_eq!(
+            Kernel::cross().offsets().collect::<Vec<_>>(),
+            [(0, -1), (-1, 0), (1, 0), (0, 1)]
+        );
+        assert_eq!(Kernel::knight().len(), 8);
+        assert!(Kernel::knight().offsets().all(|(dx, dy)| dx * dy != 0));
+        assert_eq!(Kernel::radius(2).len(), 24);
+        assert_eq!(Kernel::radius(3).len(), 48);
+        assert_eq!(Kernel::hexagonal().len(), 6);
+    }
+
+    #[test]
+    fn test_kernel_from_str() {
+        for name in ["ring", "cross", "knight", "radius-2", "radius-3"] {
+            assert_eq!(name.parse::<Kernel>().unwrap().to_string(), name);
+        }
+
+        let kernel: Kernel = "2,0 -2,0 0,3 0,-3".parse().unwrap();
+        assert_eq!(
+            kernel.offsets().collect::<Vec<_>>(),
+            [(0, -3), (-2, 0), (2, 0), (0, 3)]
+        );
+        assert_eq!(kernel.to_string(), "0,-3 -2,0 2,0 0,3");
+
+        assert!("1,0".parse::<Kernel>().is_err());
+        assert!("0,0".parse::<Kernel>().is_err());
+        assert!("4,0 -4,0".parse::<Kernel>().is_err());
+        assert!("1;0".parse::<Kernel>().is_err());
+        assert!("hexagon".parse::<Kernel>().is_err());
+    }
+}
@@ -1,51 +1,108 @@
 use std::fmt::Display;
 
 use color_eyre::owo_colors::OwoColorize;
+use rand::{Rng, RngExt, SeedableRng, rngs::StdRng};
 
 use crate::{
     CellConfig,
     game::{
-        GameState,
+        Action, GameError, GameState, Grid, HistoryEntry, Kernel, Move, PlayerView, Tiling,
+        Topology, WinCondition,
         cell::{Cell, CellData, CellState},
+        history::CellChange,
         position::Position,
+        text::count_character,
     },
 };
 
+#[cfg(feature = "serde")]
+mod save;
+
 pub struct MineField {
     started: bool,
-    width: usize,
-    height: usize,
+    grid: Grid,
     field: Vec<Cell>,
+    state: GameState,
+    win_condition: WinCondition,
+    closed_safe_cells: usize,
+    mines: usize,
+    flags: usize,
+    seed: Option<u64>,
+    rng: StdRng,
+    history: Vec<HistoryEntry>,
+    /// number of entries of the history in effect, the following ones having been undone
+    played: usize,
+    /// what the action being played changed so far
+    changes: Vec<CellChange>,
+    relocation: Option<(Position, Position)>,
 }
 
 impl MineField {
-    pub fn new(width: usize, height: usize, mut mines: u32) -> Self {
-        let size = width * height;
+    pub fn new(width: usize, height: usize, mines: u32) -> Self {
+        Self::seeded(width, height, mines, rand::random())
+    }
 
-        if mines as usize >= size {
-            panic!("Need at least one free spot");
-        }
+    pub fn try_new(width: usize, height: usize, mines: u32) -> Result<Self, GameError> {
+        Self::try_seeded(width, height, mines, rand::random())
+    }
 
-        let mut field = vec![Cell::default(); size];
+    /// draw the seed of the board from `rng`, see [`MineField::seeded`]
+    pub fn with_rng<R: Rng + ?Sized>(width: usize, height: usize, mines: u32, rng: &mut R) -> Self {
+        Self::seeded(width, height, mines, rng.next_u64())
+    }
+
+    /// the same seed always yields the same board, and so do the same moves played on it,
+    /// including the relocation of a mine hit by the first click
+    pub fn seeded(width: usize, height: usize, mines: u32, seed: u64) -> Self {
+        Self::try_seeded(width, height, mines, seed).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    pub fn try_seeded(
+        width: usize,
+        height: usize,
+        mines: u32,
+        seed: u64,
+    ) -> Result<Self, GameError> {
+        Self::try_seeded_on(Grid::new(width, height), mines, seed)
+    }
+
+    /// a board of the shape of `grid`, see [`MineField::seeded`]
+    pub fn seeded_on(grid: Grid, mines: u32, seed: u64) -> Self {
+        Self::try_seeded_on(grid, mines, seed).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    pub fn try_seeded_on(grid: Grid, mut mines: u32, seed: u64) -> Result<Self, GameError> {
+        let size = grid.checked_len().ok_or(GameError::BoardTooLarge)?;
+        let mut field = Vec::new();
+        field
+            .try_reserve_exact(size)
+            .map_err(|_| GameError::BoardTooLarge)?;
+        field.resize(size, Cell::default());
+        Self::check_size(grid.cell_count(), mines as usize)?;
+
+        let mut rng = StdRng::seed_from_u64(seed);
 
         while mines > 0 {
-            let i = rand::random_range(0..size);
+            let i = rng.random_range(0..size);
 
-            if !field[i].has_mine {
+            if !field[i].has_mine && !grid.is_masked(grid.position(i)) {
                 field[i].has_mine = true;
                 mines -= 1;
             }
         }
 
-        Self {
-            started: true,
-            width,
-            height,
-            field,
-        }
+        let mut game = Self::build(grid, field, rng);
+        game.seed = Some(seed);
+        Ok(game)
     }
 
     pub fn from<const W: usize, const H: usize>(field: [[CellConfig; W]; H]) -> Self {
+        Self::try_from(field).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    pub fn try_from<const W: usize, const H: usize>(
+        field: [[CellConfig; W]; H],
+    ) -> Result<Self, GameError> {
         let field = field
             .into_iter()
             .flatten()
@@ -67,27 +124,123 @@ impl MineField {
                     state: CellState::Flagged,
                 },
             })
-            .collect();
+            .collect::<Vec<_>>();
+
+        let mines = field.iter().filter(|cell| cell.has_mine).count();
+        Self::check_size(field.len(), mines)?;
+
+        Ok(Self::build(
+            Grid::new(W, H),
+            field,
+            StdRng::from_rng(&mut rand::rng()),
+        ))
+    }
+
+    /// a board in the middle of a game: the first click was played when a cell is open, and the game is lost when
+    /// one of the open cells holds a mine
+    pub(super) fn from_cells(grid: Grid, field: Vec<Cell>) -> Result<Self, GameError> {
+        let mines = field.iter().filter(|cell| cell.has_mine).count();
+        Self::check_size(grid.cell_count(), mines)?;
+
+        let mut game = Self::build(grid, field, StdRng::from_rng(&mut rand::rng()));
+        game.started = game.field.iter().all(|cell| cell.state != CellState::Open);
+
+        if game
+            .field
+            .iter()
+            .any(|cell| cell.has_mine && cell.state == CellState::Open)
+        {
+            game.state = GameState::Lost;
+        } else if game.closed_safe_cells == 0 {
+            game.state = GameState::Won;
+        }
+
+        Ok(game)
+    }
+
+    /// a mine hit by the first click needs a free cell to move to
+    fn check_size(cells: usize, mines: usize) -> Result<(), GameError> {
+        if cells == 0 {
+            Err(GameError::EmptyBoard)
+        } else if mines >= cells {
+            Err(GameError::TooManyMines { mines, cells })
+        } else {
+            Ok(())
+        }
+    }
+
+    fn build(grid: Grid, field: Vec<Cell>, rng: StdRng) -> Self {
+        let closed_safe_cells = grid
+            .positions()
+            .map(|pos| &field[grid.index(pos)])
+            .filter(|cell| !cell.has_mine && cell.state != CellState::Open)
+            .count();
+        let mines = field.iter().filter(|cell| cell.has_mine).count();
+        let flags = field
+            .iter()
+            .filter(|cell| cell.state == CellState::Flagged)
+            .count();
 
         Self {
             started: true,
-            width: W,
-            height: H,
+            grid,
             field,
+            state: GameState::Ongoing,
+            win_condition: WinCondition::default(),
+            closed_safe_cells,
+            mines,
+            flags,
+            seed: None,
+            rng,
+            history: Vec::new(),
+            played: 0,
+            changes: Vec::new(),
+            relocation: None,
         }
     }
 
+    /// join the edges of the board as `topology` tells, which changes the numbers its cells show
+    pub fn with_topology(mut self, topology: Topology) -> Self {
+        self.grid = self.grid.with_topology(topology);
+        self
+    }
+
+    /// make the board of cells of another shape, which changes the numbers they show
+    pub fn with_tiling(mut self, tiling: Tiling) -> Self {
+        self.grid = self.grid.with_tiling(tiling);
+        self
+    }
+
+    /// count the mines among the cells `kernel` picks around each cell, see [`Grid::with_kernel`]
+    pub fn with_kernel(mut self, kernel: Kernel) -> Self {
+        self.grid = self.grid.with_kernel(kernel);
+        self
+    }
+
+    pub fn win_condition(&self) -> WinCondition {
+        self.win_condition
+    }
+
+    /// change the rule deciding when the game is won
+    pub fn with_win_condition(mut self, win_condition: WinCondition) -> Self {
+        self.win_condition = win_condition;
+        self
+    }
+
     fn move_mine_somewhere_else(&mut self, initial: Position) {
         loop {
-            let pos = Position::random(self.width, self.height);
+            let pos = self
+                .grid
+                .position(self.rng.random_range(0..self.grid.len()));
 
-            if pos == initial {
+            if pos == initial || self.grid.is_masked(pos) {
                 continue;
             }
 
             let cell = self.get_cell_mut(pos);
             if !cell.has_mine {
                 cell.has_mine = true;
+                self.relocation = Some((initial, pos));
                 break;
             }
         }
@@ -96,18 +249,141 @@ impl MineField {
         cell.has_mine = false;
     }
 
-    pub fn play(&mut self, pos: Position, flag: bool) -> GameState {
-        if flag {
-            let cell = self.get_cell_mut(pos);
-            if cell.state == CellState::Flagged {
-                cell.state = CellState::Closed;
-            } else {
-                cell.state = CellState::Flagged;
+    /// play a move, ignoring it when it cannot be played, see [`MineField::try_play`]
+    pub fn play(&mut self, next_move: impl Into<Move>) -> GameState {
+        self.try_play(next_move).unwrap_or(self.state)
+    }
+
+    /// play a move, leaving the field untouched when it cannot be played
+    /// a batch is played up to its first action which cannot be, or until the game is over
+    pub fn try_play(&mut self, next_move: impl Into<Move>) -> Result<GameState, GameError> {
+        self.apply(&next_move.into().action)?;
+        Ok(self.state)
+    }
+
+    fn apply(&mut self, action: &Action) -> Result<(), GameError> {
+        if self.state != GameState::Ongoing {
+            return Err(GameError::GameOver(self.state));
+        }
+
+        if let Action::Batch(actions) = action {
+            for action in actions {
+                self.apply(action)?;
+
+                if self.state != GameState::Ongoing {
+                    break;
+                }
             }
 
-            return GameState::Ongoing;
+            return Ok(());
+        }
+
+        self.check_action(action)?;
+
+        let previous_state = self.state;
+        let started = self.started;
+
+        match action {
+            Action::Open(pos) => self.open(*pos),
+            Action::Flag(pos) => self.mark(*pos, CellState::Flagged),
+            Action::Unflag(pos) => {
+                if self.get_cell(*pos).state == CellState::Flagged {
+                    self.mark(*pos, CellState::Closed)
+                }
+            }
+            Action::ToggleFlag(pos) => {
+                if self.get_cell(*pos).state == CellState::Flagged {
+                    self.mark(*pos, CellState::Closed)
+                } else {
+                    self.mark(*pos, CellState::Flagged)
+                }
+            }
+            Action::QuestionMark(pos) => {
+                if self.get_cell(*pos).state == CellState::Questioned {
+                    self.mark(*pos, CellState::Closed)
+                } else {
+                    self.mark(*pos, CellState::Questioned)
+                }
+            }
+            Action::Chord(pos) => self.chord(*pos),
+            Action::Batch(_) => unreachable!("Batches are played action by action"),
         }
 
+        self.history.truncate(self.played);
+        self.history.push(HistoryEntry {
+            action: action.clone(),
+            state: self.state,
+            previous_state,
+            started: (started, self.started),
+            relocation: self.relocation.take(),
+            changes: std::mem::take(&mut self.changes),
+        });
+        self.played += 1;
+
+        Ok(())
+    }
+
+    fn check_action(&self, action: &Action) -> Result<(), GameError> {
+        match action {
+            Action::Open(pos) if self.try_cell_data(*pos)? == CellData::Flagged => {
+                return Err(GameError::Flagged(*pos));
+            }
+            Action::Open(pos)
+            | Action::Flag(pos)
+            | Action::Unflag(pos)
+            | Action::ToggleFlag(pos)
+            | Action::QuestionMark(pos) => {
+                if self.try_cell_data(*pos)?.get_mines_count().is_some() {
+                    return Err(GameError::AlreadyOpen(*pos));
+                }
+            }
+            Action::Chord(pos) => {
+                if self.try_cell_data(*pos)?.get_mines_count().is_none() {
+                    return Err(GameError::NotOpen(*pos));
+                }
+            }
+            Action::Batch(actions) => {
+                for action in actions {
+                    self.check_action(action)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// change the state of a cell, recording it for the history
+    fn set_cell_state(&mut self, pos: Position, state: CellState) -> CellState {
+        let cell = self.get_cell_mut(pos);
+        let before = std::mem::replace(&mut cell.state, state.clone());
+        self.changes.push(CellChange {
+            pos,
+            before: before.clone(),
+            after: state,
+        });
+        before
+    }
+
+    /// change the mark the player put on a cell which is not open yet
+    fn mark(&mut self, pos: Position, state: CellState) {
+        if self.get_cell(pos).state == CellState::Open {
+            return;
+        }
+
+        let previous = self.set_cell_state(pos, state);
+        if previous == CellState::Flagged {
+            self.flags -= 1;
+        }
+        if self.get_cell(pos).state == CellState::Flagged {
+            self.flags += 1;
+        }
+
+        if self.win_condition == WinCondition::FlagAllMines && self.all_mines_flagged() {
+            self.state = GameState::Won;
+        }
+    }
+
+    fn open(&mut self, pos: Position) {
         let cell = self.get_cell(pos);
         if cell.has_mine && self.started {
             self.move_mine_somewhere_else(pos);
@@ -115,25 +391,187 @@ impl MineField {
 
         self.started = false;
 
-        let cell = self.get_cell_mut(pos);
-
-        if cell.has_mine {
-            cell.state = CellState::Open;
-            return GameState::Lost;
+        if self.get_cell(pos).has_mine {
+            if self.set_cell_state(pos, CellState::Open) == CellState::Flagged {
+                self.flags -= 1;
+            }
+            self.state = GameState::Lost;
+            return;
         }
 
         let mut opener = Opener::new(self);
         opener.open(pos);
 
-        return GameState::Ongoing;
+        if self.win_condition == WinCondition::OpenAllSafe && self.closed_safe_cells == 0 {
+            self.state = GameState::Won;
+        }
+    }
+
+    /// open every closed neighbour of an open cell showing as many mines as it has flagged neighbours
+    /// does nothing when the counts differ, and loses the game when one of the flags is misplaced
+    fn chord(&mut self, pos: Position) {
+        let CellData::Open(mines) = self.cell_data(pos) else {
+            return;
+        };
+
+        let neighbours = pos.neighbours(self);
+        let flags = neighbours
+            .iter()
+            .filter(|neighbour| self.get_cell(**neighbour).state == CellState::Flagged)
+            .count();
+
+        if flags != mines {
+            return;
+        }
+
+        for neighbour in neighbours {
+            if self.cell_data(neighbour).is_closed() {
+                self.open(neighbour);
+            }
+
+            if self.state != GameState::Ongoing {
+                return;
+            }
+        }
+    }
+
+    /// whether every mine is flagged and no flag sits on a safe cell
+    fn all_mines_flagged(&self) -> bool {
+        self.field
+            .iter()
+            .all(|cell| cell.has_mine == (cell.state == CellState::Flagged))
+    }
+
+    pub fn state(&self) -> GameState {
+        self.state
+    }
+
+    /// total number of mines hidden in the field
+    pub fn mines(&self) -> usize {
+        self.mines
+    }
+
+    /// number of mines minus the number of flags placed, as a player would count them
+    pub fn remaining_mines(&self) -> isize {
+        self.mines as isize - self.flags as isize
     }
 
-    fn get_cell(&self, pos: Position) -> &Cell {
-        &self.field[pos.to_index(self.width)]
+    /// actions played so far and what they changed, batches being recorded action by action
+    pub fn history(&self) -> &[HistoryEntry] {
+        &self.history[..self.played]
+    }
+
+    /// the board before the actions of its history were played, with the mine hit by the first click already moved
+    /// away, so that playing the same actions again leads to the same game
+    ///
+    /// such a board no longer is the one its seed generates, and is left without one
+    pub fn starting_board(&self) -> MineField {
+        let mut board = Self::build(
+            self.grid.clone(),
+            self.field.clone(),
+            StdRng::from_rng(&mut rand::rng()),
+        );
+        board.started = self.started;
+        board.state = self.state;
+        board.win_condition = self.win_condition;
+        board.seed = self.seed;
+        board.history = self.history().to_vec();
+        board.played = self.played;
+        while board.undo().is_some() {}
+
+        for (initial, moved) in self.history().iter().filter_map(|entry| entry.relocation) {
+            board.get_cell_mut(initial).has_mine = false;
+            board.get_cell_mut(moved).has_mine = true;
+            board.seed = None;
+        }
+        board.history.clear();
+        board.recount();
+
+        board
+    }
+
+    /// take back the last action played, returning it
+    pub fn undo(&mut self) -> Option<Action> {
+        self.played = self.played.checked_sub(1)?;
+        let entry = &self.history[self.played];
+
+        for change in entry.changes.iter().rev() {
+            let index = self.grid.index(change.pos);
+            self.field[index].state = change.before.clone();
+        }
+        if let Some((initial, moved)) = entry.relocation {
+            let (initial, moved) = (self.grid.index(initial), self.grid.index(moved));
+            self.field[initial].has_mine = true;
+            self.field[moved].has_mine = false;
+        }
+        self.started = entry.started.0;
+        self.state = entry.previous_state;
+        let action = entry.action.clone();
+
+        self.recount();
+        Some(action)
+    }
+
+    /// play again the last action taken back, returning it
+    pub fn redo(&mut self) -> Option<Action> {
+        let entry = self.history.get(self.played)?;
+        self.played += 1;
+
+        if let Some((initial, moved)) = entry.relocation {
+            let (initial, moved) = (self.grid.index(initial), self.grid.index(moved));
+            self.field[initial].has_mine = false;
+            self.field[moved].has_mine = true;
+        }
+        for change in &entry.changes {
+            let index = self.grid.index(change.pos);
+            self.field[index].state = change.after.clone();
+        }
+        self.started = entry.started.1;
+        self.state = entry.state;
+        let action = entry.action.clone();
+
+        self.recount();
+        Some(action)
+    }
+
+    /// count again the flags and the safe cells left to open, after cells changed outside of the game rules
+    fn recount(&mut self) {
+        self.flags = self
+            .field
+            .iter()
+            .filter(|cell| cell.state == CellState::Flagged)
+            .count();
+        self.closed_safe_cells = self
+            .grid
+            .positions()
+            .map(|pos| self.get_cell(pos))
+            .filter(|cell| !cell.has_mine && cell.state != CellState::Open)
+            .count();
+    }
+
+    /// seed the board was generated from, if it was generated at all
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    pub(super) fn get_cell(&self, pos: Position) -> &Cell {
+        &self.field[self.grid.index(pos)]
     }
 
     fn get_cell_mut(&mut self, pos: Position) -> &mut Cell {
-        self.field.get_mut(pos.to_index(self.width)).unwrap()
+        &mut self.field[self.grid.index(pos)]
+    }
+
+    pub fn contains(&self, pos: Position) -> bool {
+        self.grid.contains(pos)
+    }
+
+    pub fn try_cell_data(&self, pos: Position) -> Result<CellData, GameError> {
+        if self.contains(pos) {
+            Ok(self.cell_data(pos))
+        } else {
+            Err(GameError::OutOfBounds(pos))
+        }
     }
 
     fn neighbouring_mines(&self, pos: Position) -> usize {
@@ -148,49 +586,116 @@ impl MineField {
         match cell.state {
             CellState::Closed => CellData::Closed,
             CellState::Flagged => CellData::Flagged,
+            CellState::Questioned => CellData::Questioned,
             CellState::Open => CellData::Open(self.neighbouring_mines(pos)),
         }
     }
 
+    /// coloured character a cell is rendered with, revealing the mines left closed once the game is lost, and a blank
+    /// for a hole
+    pub fn cell_glyph(&self, pos: Position) -> String {
+        if self.grid.is_masked(pos) {
+            return " ".to_string();
+        }
+
+        let cell = self.get_cell(pos);
+        match cell.state {
+            CellState::Closed | CellState::Questioned
+                if cell.has_mine && self.state == GameState::Lost =>
+            {
+                "*".red().to_string()
+            }
+            CellState::Closed => "o".to_string(),
+            CellState::Flagged => "F".black().to_string(),
+            CellState::Questioned => "?".to_string(),
+            CellState::Open if cell.has_mine => "X".on_red().blink().to_string(),
+            CellState::Open => match self.neighbouring_mines(pos) {
+                0 => " ".black().to_string(),
+                1 => "1".bright_blue().to_string(),
+                2 => "2".green().to_string(),
+                3 => "3".red().to_string(),
+                4 => "4".blue().to_string(),
+                5 => "5".yellow().to_string(),
+                6 => "6".cyan().to_string(),
+                7 => "7".black().to_string(),
+                8 => "8".bright_black().to_string(),
+                mines => count_character(mines).magenta().to_string(),
+            },
+        }
+    }
+
+    /// what a player can see of the game
+    pub fn view(&self) -> PlayerView {
+        PlayerView {
+            grid: self.grid.clone(),
+            mines: self.mines,
+            cells: self
+                .grid
+                .all_positions()
+                .map(|pos| self.cell_data(pos))
+                .collect(),
+        }
+    }
+
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
     pub fn width(&self) -> usize {
-        self.width
+        self.grid.width()
     }
 
     pub fn height(&self) -> usize {
-        self.height
+        self.grid.height()
+    }
+}
+
+impl AsRef<Grid> for MineField {
+    fn as_ref(&self) -> &Grid {
+        &self.grid
     }
 }
 
+/// edges joined to the opposite one are drawn with `~`, hexagonal cells are spaced out, each row being shifted half
+/// a cell further than the one above, and the layers of a three-dimensional board come one after the other
 impl Display for MineField {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&"—".repeat(self.width))?;
-
-        for (y, row) in self.field.chunks(self.width).enumerate() {
-            f.write_str("\n")?;
-            for (x, cell) in row.iter().enumerate() {
-                match cell.state {
-                    CellState::Closed => f.write_str("o")?,
-                    CellState::Flagged => f.write_str(&"F".black().to_string())?,
-                    CellState::Open if cell.has_mine => {
-                        f.write_str(&"X".on_red().blink().to_string())?
-                    }
-                    CellState::Open => {
-                        let mines = self.neighbouring_mines((x, y).into());
-                        let string = match mines {
-                            0 => " ".black().into_styled(),
-                            1 => "1".bright_blue().into_styled(),
-                            2 => "2".green().into_styled(),
-                            3 => "3".red().into_styled(),
-                            4 => "4".blue().into_styled(),
-                            5 => "5".yellow().into_styled(),
-                            6 => "6".cyan().into_styled(),
-                            7 => "7".black().into_styled(),
-                            8 => "8".bright_black().into_styled(),
-                            _ => unreachable!("Invalid value of mines"),
-                        };
-                        f.write_str(&string.to_string())?;
+        let topology = self.grid.topology();
+        let hexagonal = self.grid.tiling() == Tiling::Hexagonal;
+        let side = if topology.wraps_horizontally() {
+            "~"
+        } else {
+            ""
+        };
+        let top = if topology.wraps_vertically() {
+            "~"
+        } else {
+            "—"
+        };
+        let row_width = match hexagonal {
+            true => 2 * self.width() + self.height() - 2,
+            false => self.width(),
+        };
+
+        for z in 0..self.grid.depth() {
+            if z > 0 {
+                f.write_str("\n")?;
+            }
+            f.write_str(&top.repeat(row_width + 2 * side.len()))?;
+
+            for y in 0..self.height() {
+                f.write_str("\n")?;
+                if hexagonal {
+                    f.write_str(&" ".repeat(y))?;
+                }
+                f.write_str(side)?;
+                for x in 0..self.width() {
+                    if hexagonal && x > 0 {
+                        f.write_str(" ")?;
                     }
-                };
+                    f.write_str(&self.cell_glyph((x, y, z).into()))?;
+                }
+                f.write_str(side)?;
             }
         }
 
@@ -209,20 +714,367 @@ impl<'a> Opener<'a> {
         Self { game, visited }
     }
 
+    /// open `pos`, and the cells around it while they show no mine, depth first with a stack of its own rather than
+    /// recursion, which large boards would overflow
     fn open(&mut self, pos: Position) {
-        if self.visited[pos.to_index(self.game.width)] {
-            return;
-        }
+        let mut stack = vec![pos];
 
-        self.visited[pos.to_index(self.game.width)] = true;
-        self.game.get_cell_mut(pos).state = CellState::Open;
+        while let Some(pos) = stack.pop() {
+            if self.visited[self.game.grid.index(pos)] {
+                continue;
+            }
 
-        if self.game.neighbouring_mines(pos) > 0 {
-            return;
+            self.visited[self.game.grid.index(pos)] = true;
+
+            if self.game.get_cell(pos).state == CellState::Open {
+                continue;
+            }
+
+            if self.game.set_cell_state(pos, CellState::Open) == CellState::Flagged {
+                self.game.flags -= 1;
+            }
+            self.game.closed_safe_cells -= 1;
+
+            if self.game.neighbouring_mines(pos) > 0 {
+                continue;
+            }
+
+            // in reverse, so that neighbours are opened in the order of the grid
+            stack.extend(pos.neighbours(self.game).into_iter().rev());
         }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_won_when_last_safe_cell_opens() {
+        let mut game = MineField::from([
+            [CellConfig::Closed, CellConfig::Mine, CellConfig::Closed],
+            [CellConfig::Closed, CellConfig::Closed, CellConfig::Closed],
+        ]);
+
+        assert_eq!(game.play(Action::Open((0, 0).into())), GameState::Ongoing);
+        assert_eq!(game.play(Action::Open((2, 0).into())), GameState::Ongoing);
+        assert_eq!(game.play(Action::Open((0, 1).into())), GameState::Ongoing);
+        assert_eq!(game.play(Action::Open((1, 1).into())), GameState::Ongoing);
+        assert_eq!(game.play(Action::Open((2, 1).into())), GameState::Won);
+        assert_eq!(game.state(), GameState::Won);
+    }
+
+    #[test]
+    fn test_lost_is_final() {
+        let mut game = MineField::from([[CellConfig::Open, CellConfig::Mine, CellConfig::Closed]]);
+        game.started = false;
+
+        assert_eq!(game.play(Action::Open((1, 0).into())), GameState::Lost);
+        assert_eq!(game.play(Action::Open((2, 0).into())), GameState::Lost);
+        assert_eq!(game.state(), GameState::Lost);
+    }
+
+    #[test]
+    fn test_won_when_all_mines_flagged() {
+        let mut game = MineField::from([[CellConfig::Mine, CellConfig::Closed, CellConfig::Mine]])
+            .with_win_condition(WinCondition::FlagAllMines);
+
+        assert_eq!(
+            game.play(Action::ToggleFlag((0, 0).into())),
+            GameState::Ongoing
+        );
+        assert_eq!(
+            game.play(Action::ToggleFlag((1, 0).into())),
+            GameState::Ongoing
+        );
+        assert_eq!(
+            game.play(Action::ToggleFlag((2, 0).into())),
+            GameState::Ongoing
+        );
+        assert_eq!(game.play(Action::ToggleFlag((1, 0).into())), GameState::Won);
+    }
+
+    #[test]
+    fn test_chord_opens_closed_neighbours() {
+        let mut game = MineField::from([
+            [CellConfig::Flagged, CellConfig::Closed, CellConfig::Closed],
+            [CellConfig::Closed, CellConfig::Closed, CellConfig::Closed],
+            [CellConfig::Closed, CellConfig::Closed, CellConfig::Mine],
+        ]);
+        game.started = false;
+
+        assert_eq!(game.play(Action::Open((1, 1).into())), GameState::Ongoing);
+        assert_eq!(game.play(Action::Chord((1, 1).into())), GameState::Ongoing);
+        assert!(game.cell_data((1, 1).into()) == CellData::Open(2));
+
+        assert_eq!(
+            game.play(Action::ToggleFlag((2, 2).into())),
+            GameState::Ongoing
+        );
+        assert_eq!(game.play(Action::Chord((1, 1).into())), GameState::Won);
+    }
+
+    #[test]
+    fn test_chord_with_misplaced_flag_loses() {
+        let mut game = MineField::from([
+            [CellConfig::Closed, CellConfig::Mine, CellConfig::Closed],
+            [CellConfig::Closed, CellConfig::Closed, CellConfig::Closed],
+        ]);
+        game.started = false;
+
+        assert_eq!(game.play(Action::Open((1, 1).into())), GameState::Ongoing);
+        assert_eq!(
+            game.play(Action::ToggleFlag((0, 0).into())),
+            GameState::Ongoing
+        );
+        assert_eq!(game.play(Action::Chord((1, 1).into())), GameState::Lost);
+    }
+
+    #[test]
+    fn test_marks_and_batch() {
+        let mut game = MineField::from([[
+            CellConfig::Closed,
+            CellConfig::Mine,
+            CellConfig::Closed,
+            CellConfig::Closed,
+        ]]);
+        game.started = false;
+
+        game.play(Action::Batch(vec![
+            Action::QuestionMark((0, 0).into()),
+            Action::Flag((1, 0).into()),
+            Action::Flag((1, 0).into()),
+            Action::Flag((2, 0).into()),
+            Action::Unflag((2, 0).into()),
+        ]));
+        assert_eq!(game.cell_data((0, 0).into()), CellData::Questioned);
+        assert_eq!(game.cell_data((1, 0).into()), CellData::Flagged);
+        assert_eq!(game.cell_data((2, 0).into()), CellData::Closed);
+        assert_eq!(game.remaining_mines(), 0);
+
+        let state = game.play(Action::Batch(vec![
+            Action::Open((0, 0).into()),
+            Action::Unflag((1, 0).into()),
+            Action::Open((1, 0).into()),
+            Action::Open((3, 0).into()),
+        ]));
+        assert_eq!(state, GameState::Lost);
+        assert_eq!(game.cell_data((0, 0).into()), CellData::Open(1));
+        assert_eq!(game.cell_data((3, 0).into()), CellData::Closed);
+    }
+
+    #[test]
+    fn test_invalid_boards_and_moves() {
+        assert_eq!(
+            MineField::try_new(0, 4, 0).err(),
+            Some(GameError::EmptyBoard)
+        );
+        assert_eq!(
+            MineField::try_new(usize::MAX, 2, 1).err(),
+            Some(GameError::BoardTooLarge)
+        );
+        assert_eq!(
+            MineField::try_new(usize::MAX / 2, 1, 1).err(),
+            Some(GameError::BoardTooLarge)
+        );
+        assert_eq!(
+            MineField::try_new(2, 2, 4).err(),
+            Some(GameError::TooManyMines { mines: 4, cells: 4 })
+        );
+        assert_eq!(
+            MineField::try_from([[CellConfig::Mine]]).err(),
+            Some(GameError::TooManyMines { mines: 1, cells: 1 })
+        );
+
+        let mut game = MineField::from([[CellConfig::Open, CellConfig::Mine, CellConfig::Closed]]);
+        game.started = false;
+
+        assert_eq!(
+            game.try_play(Action::Open((3, 0).into())),
+            Err(GameError::OutOfBounds((3, 0).into()))
+        );
+        assert_eq!(
+            game.try_play(Action::Flag((0, 0).into())),
+            Err(GameError::AlreadyOpen((0, 0).into()))
+        );
+        assert_eq!(
+            game.try_play(Action::Chord((2, 0).into())),
+            Err(GameError::NotOpen((2, 0).into()))
+        );
+        game.play(Action::Flag((1, 0).into()));
+        assert_eq!(
+            game.try_play(Action::Open((1, 0).into())),
+            Err(GameError::Flagged((1, 0).into()))
+        );
+        assert_eq!(game.remaining_mines(), 0);
+        game.play(Action::Unflag((1, 0).into()));
+        assert_eq!(
+            game.try_play(Action::Open((1, 0).into())),
+            Ok(GameState::Lost)
+        );
+        assert_eq!(
+            game.try_play(Action::Open((2, 0).into())),
+            Err(GameError::GameOver(GameState::Lost))
+        );
+    }
+
+    #[test]
+    fn test_open_large_board() {
+        let mut game = MineField::try_seeded(300, 300, 10, 0).unwrap();
+
+        // so few mines that the first click opens most of the board, the flood going far from it
+        assert!(game.try_play(Action::Open((150, 150).into())).is_ok());
+        assert!(game.history()[0].revealed().len() > 80_000);
+    }
 
-        for neighbour in pos.neighbours(self.game) {
-            self.open(neighbour);
+    #[test]
+    fn test_same_seed_same_board() {
+        let mines = |game: &MineField| -> Vec<bool> {
+            game.field.iter().map(|cell| cell.has_mine).collect()
+        };
+
+        for seed in 0..20 {
+            let mut first = MineField::seeded(8, 8, 40, seed);
+            let mut second = MineField::seeded(8, 8, 40, seed);
+            assert_eq!(mines(&first), mines(&second));
+
+            first.play(Action::Open((4, 4).into()));
+            second.play(Action::Open((4, 4).into()));
+            assert_eq!(mines(&first), mines(&second));
         }
     }
+
+    #[test]
+    fn test_undo_and_redo() {
+        let mut game = MineField::seeded(8, 8, 40, 1);
+        let initial = game.to_text();
+
+        game.play(Action::Open((4, 4).into()));
+        let opened = game.to_text();
+        let revealed = game.history()[0].revealed();
+        assert!(revealed.contains(&(4, 4).into()));
+
+        game.play(Action::Flag((0, 0).into()));
+        assert_eq!(game.history().len(), 2);
+
+        assert_eq!(game.undo(), Some(Action::Flag((0, 0).into())));
+        assert_eq!(game.to_text(), opened);
+        assert_eq!(game.remaining_mines(), 40);
+
+        // the mine moved away from the first click comes back
+        assert_eq!(game.undo(), Some(Action::Open((4, 4).into())));
+        assert_eq!(game.to_text(), initial);
+        assert_eq!(game.undo(), None);
+
+        assert_eq!(game.redo(), Some(Action::Open((4, 4).into())));
+        assert_eq!(game.to_text(), opened);
+        assert_eq!(game.history()[0].revealed(), revealed);
+
+        // playing after an undo forgets the actions taken back
+        game.play(Action::Flag((7, 7).into()));
+        assert_eq!(game.redo(), None);
+        assert_eq!(game.history().len(), 2);
+    }
+
+    #[test]
+    fn test_undo_lost_game() {
+        let mut game = MineField::from([[CellConfig::Open, CellConfig::Mine, CellConfig::Closed]]);
+        game.started = false;
+
+        assert_eq!(game.play(Action::Open((1, 0).into())), GameState::Lost);
+        assert_eq!(game.history()[0].state, GameState::Lost);
+
+        game.undo();
+        assert_eq!(game.state(), GameState::Ongoing);
+        assert_eq!(game.play(Action::Open((2, 0).into())), GameState::Won);
+    }
+
+    #[test]
+    fn test_flood_fill_wraps_around() {
+        let mut game = MineField::from([
+            [CellConfig::Closed, CellConfig::Closed, CellConfig::Closed],
+            [CellConfig::Closed, CellConfig::Closed, CellConfig::Closed],
+            [CellConfig::Closed, CellConfig::Closed, CellConfig::Closed],
+            [CellConfig::Mine, CellConfig::Closed, CellConfig::Closed],
+            [CellConfig::Closed, CellConfig::Closed, CellConfig::Closed],
+        ])
+        .with_topology(Topology::Torus);
+
+        game.play(Action::Open((2, 1).into()));
+        assert_eq!(game.cell_data((0, 0).into()), CellData::Open(0));
+        // the last row is only reached through the top edge, and on a board three cells wide every cell of the rows
+        // around the mine neighbours it
+        assert_eq!(game.cell_data((2, 4).into()), CellData::Open(1));
+        assert_eq!(game.cell_data((2, 2).into()), CellData::Open(1));
+        assert_eq!(game.cell_data((2, 3).into()), CellData::Closed);
+    }
+
+    #[test]
+    fn test_masked_board() {
+        let mut game: MineField = "..#..\n..#.*\n".parse().unwrap();
+
+        // the hole neighbours no cell, so the opening stops at it and the cells past it only count the mine
+        game.play(Action::Open((0, 0).into()));
+        assert_eq!(game.to_text(), "  #..\n  #.*\n");
+        game.play(Action::Open((3, 0).into()));
+        game.play(Action::Open((4, 0).into()));
+        assert_eq!(game.play(Action::Open((3, 1).into())), GameState::Won);
+        assert_eq!(
+            game.try_cell_data((2, 0).into()),
+            Err(GameError::OutOfBounds((2, 0).into()))
+        );
+
+        // a disc of 21 cells in a square of 25
+        let grid = Grid::new(5, 5)
+            .with_mask(|pos| pos.x().abs_diff(2).pow(2) + pos.y().abs_diff(2).pow(2) <= 5);
+        let game = MineField::seeded_on(grid, 20, 1);
+        assert_eq!(game.to_text().matches('*').count(), 20);
+        assert_eq!(game.to_text().matches('#').count(), 4);
+        assert_eq!(
+            MineField::try_seeded_on(game.grid().clone(), 21, 1).err(),
+            Some(GameError::TooManyMines {
+                mines: 21,
+                cells: 21
+            })
+        );
+    }
+
+    #[test]
+    fn test_kernel_board() {
+        let mut game = MineField::from([
+            [CellConfig::Closed, CellConfig::Closed, CellConfig::Closed],
+            [CellConfig::Closed, CellConfig::Closed, CellConfig::Closed],
+            [CellConfig::Closed, CellConfig::Closed, CellConfig::Mine],
+        ])
+        .with_kernel(Kernel::cross());
+
+        // the mine is only diagonal to the centre, which counts the four cells sharing a side with it
+        game.play(Action::Open((0, 0).into()));
+        assert_eq!(game.cell_data((1, 1).into()), CellData::Open(0));
+        assert_eq!(game.cell_data((2, 1).into()), CellData::Open(1));
+        assert_eq!(game.state(), GameState::Won);
+    }
+
+    #[test]
+    fn test_hexagonal_board() {
+        let mut game = MineField::from([
+            [CellConfig::Closed, CellConfig::Closed, CellConfig::Closed],
+            [CellConfig::Closed, CellConfig::Closed, CellConfig::Mine],
+        ])
+        .with_tiling(Tiling::Hexagonal);
+
+        // (1, 0) would neighbour the mine on a square board
+        game.play(Action::Open((0, 0).into()));
+        assert_eq!(game.cell_data((1, 0).into()), CellData::Open(0));
+        assert_eq!(game.cell_data((2, 0).into()), CellData::Open(1));
+        assert_eq!(game.cell_data((1, 1).into()), CellData::Open(1));
+        assert_eq!(game.state(), GameState::Won);
+        assert_eq!(
+            game.to_string()
+                .lines()
+                .nth(2)
+                .map(|row| row.starts_with(' ')),
+            Some(true)
+        );
+    }
 }
@@ -0,0 +1,313 @@
+use rand::{SeedableRng, rngs::StdRng};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error};
+
+use super::MineField;
+use crate::game::{
+    GameError, GameState, Grid, HistoryEntry, Kernel, Tiling, Topology, WinCondition,
+    cell::{Cell, CellState},
+};
+
+/// what is saved of a game, everything else being derived from it when loading
+#[derive(Serialize, Deserialize)]
+struct SavedGame {
+    width: usize,
+    height: usize,
+    #[serde(default = "flat")]
+    depth: usize,
+    #[serde(default)]
+    topology: Topology,
+    #[serde(default)]
+    tiling: Tiling,
+    /// `None` when the cells neighbour those touching them
+    #[serde(default)]
+    kernel: Option<Kernel>,
+    /// indices of the masked positions
+    #[serde(default)]
+    holes: Vec<usize>,
+    /// indices of the cells holding a mine
+    mines: Vec<usize>,
+    cells: Vec<CellState>,
+    started: bool,
+    state: GameState,
+    win_condition: WinCondition,
+    seed: Option<u64>,
+    history: Vec<HistoryEntry>,
+    /// number of entries of the history in effect, the following ones having been undone
+    played: usize,
+}
+
+fn flat() -> usize {
+    1
+}
+
+impl From<&MineField> for SavedGame {
+    fn from(game: &MineField) -> Self {
+        Self {
+            width: game.width(),
+            height: game.height(),
+            depth: game.grid.depth(),
+            topology: game.grid.topology(),
+            tiling: game.grid.tiling(),
+            kernel: (game.grid.kernel() != game.grid.tiling().kernel()).then(|| game.grid.kernel()),
+            holes: game
+                .grid
+                .all_positions()
+                .filter(|pos| game.grid.is_masked(*pos))
+                .map(|pos| game.grid.index(pos))
+                .collect(),
+            mines: (0..game.field.len())
+                .filter(|index| game.field[*index].has_mine)
+                .collect(),
+            cells: game.field.iter().map(|cell| cell.state.clone()).collect(),
+            started: game.started,
+            state: game.state,
+            win_condition: game.win_condition,
+            seed: game.seed,
+            history: game.history.clone(),
+            played: game.played,
+        }
+    }
+}
+
+impl TryFrom<SavedGame> for MineField {
+    type Error = GameError;
+
+    fn try_from(saved: SavedGame) -> Result<Self, Self::Error> {
+        let invalid = |reason: &str| GameError::InvalidSave(reason.to_string());
+
+        let size = saved
+            .width
+            .checked_mul(saved.height)
+            .and_then(|size| size.checked_mul(saved.depth))
+            .ok_or_else(|| invalid("the board is too large"))?;
+        if saved.cells.len() != size {
+            return Err(GameError::InvalidSave(format!(
+                "{} cells on a board of {}x{}x{}",
+                saved.cells.len(),
+                saved.width,
+                saved.height,
+                saved.depth
+            )));
+        }
+
+        let mut playable = vec![true; size];
+        for index in &saved.holes {
+            *playable
+                .get_mut(*index)
+                .ok_or_else(|| invalid("a hole is out of the board"))? = false;
+        }
+        let mut grid = Grid::new(saved.width, saved.height)
+            .with_depth(saved.depth)
+            .with_topology(saved.topology)
+            .with_tiling(saved.tiling);
+        if let Some(kernel) = saved.kernel {
+            grid = grid.with_kernel(kernel);
+        }
+        let shape = grid.clone();
+        let grid = grid.with_mask(|pos| playable[shape.index(pos)]);
+
+        let mut field: Vec<Cell> = saved
+            .cells
+            .into_iter()
+            .map(|state| Cell {
+                has_mine: false,
+                state,
+            })
+            .collect();
+        for index in &saved.mines {
+            let cell = field
+                .get_mut(*index)
+                .ok_or_else(|| invalid("a mine is out of the board"))?;
+            if cell.has_mine {
+                return Err(invalid("a cell holds two mines"));
+            }
+            if !playable[*index] {
+                return Err(invalid("a mine is in a hole"));
+            }
+            cell.has_mine = true;
+        }
+        Self::check_size(grid.cell_count(), saved.mines.len())?;
+
+        if saved.started && field.iter().any(|cell| cell.state == CellState::Open) {
+            return Err(invalid("cells are open before the first click"));
+        }
+        if field
+            .iter()
+            .zip(&playable)
+            .any(|(cell, playable)| !playable && cell.state != CellState::Closed)
+        {
+            return Err(invalid("a hole is played on"));
+        }
+
+        // the generator is only used by the first click, which should move mines as it would have without saving
+        let rng = match saved.seed {
+            Some(seed) if saved.started => {
+                let generated = Self::try_seeded_on(grid.clone(), saved.mines.len() as u32, seed)?;
+                if generated
+                    .field
+                    .iter()
+                    .zip(&field)
+                    .any(|(generated, saved)| generated.has_mine != saved.has_mine)
+                {
+                    return Err(invalid("the mines are not where the seed puts them"));
+                }
+                generated.rng
+            }
+            _ => StdRng::from_rng(&mut rand::rng()),
+        };
+
+        if saved.played > saved.history.len() {
+            return Err(invalid("more actions are played than the history has"));
+        }
+        if saved.history.iter().any(|entry| {
+            entry
+                .changes
+                .iter()
+                .any(|change| !grid.contains(change.pos))
+                || entry.relocation.is_some_and(|(initial, moved)| {
+                    !grid.contains(initial) || !grid.contains(moved)
+                })
+        }) {
+            return Err(invalid("the history changes cells out of the board"));
+        }
+
+        let mut game = Self::build(grid, field, rng);
+        game.started = saved.started;
+        game.win_condition = saved.win_condition;
+        game.seed = saved.seed;
+        game.history = saved.history;
+        game.played = saved.played;
+
+        let lost = game
+            .field
+            .iter()
+            .any(|cell| cell.has_mine && cell.state == CellState::Open);
+        let won = match game.win_condition {
+            WinCondition::OpenAllSafe => game.closed_safe_cells == 0,
+            WinCondition::FlagAllMines => game.all_mines_flagged(),
+        };
+        let state = match (lost, won) {
+            (true, _) => GameState::Lost,
+            (false, true) => GameState::Won,
+            (false, false) => GameState::Ongoing,
+        };
+        if state != saved.state {
+            return Err(GameError::InvalidSave(format!(
+                "the game is saved as {:?} but its cells make it {state:?}",
+                saved.state
+            )));
+        }
+        game.state = state;
+
+        Ok(game)
+    }
+}
+
+impl Serialize for MineField {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SavedGame::from(self).serialize(serializer)
+    }
+}
+
+/// checks the saved game is one a game could have led to
+impl<'de> Deserialize<'de> for MineField {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        SavedGame::deserialize(deserializer)?
+            .try_into()
+            .map_err(D::Error::custom)
+    }
+}
+
+impl MineField {
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// compact binary form of the game
+    pub fn to_bytes(&self) -> postcard::Result<Vec<u8>> {
+        postcard::to_stdvec(self)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> postcard::Result<Self> {
+        postcard::from_bytes(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Action;
+
+    #[test]
+    fn test_save_mid_game() {
+        let mut game = MineField::seeded(16, 16, 40, 7)
+            .with_topology(Topology::Torus)
+            .with_tiling(Tiling::Hexagonal);
+        game.play(Action::Open((8, 8).into()));
+        game.play(Action::Flag((0, 0).into()));
+
+        for loaded in [
+            MineField::from_json(&game.to_json().unwrap()).unwrap(),
+            MineField::from_bytes(&game.to_bytes().unwrap()).unwrap(),
+        ] {
+            assert_eq!(loaded.to_text(), game.to_text());
+            assert_eq!(loaded.grid(), game.grid());
+            assert_eq!(loaded.history(), game.history());
+            assert_eq!(loaded.state(), game.state());
+            assert_eq!(loaded.remaining_mines(), game.remaining_mines());
+            assert_eq!(loaded.seed(), Some(7));
+
+            let mut loaded = loaded;
+            assert_eq!(loaded.undo(), Some(Action::Flag((0, 0).into())));
+            assert_eq!(loaded.remaining_mines(), 40);
+        }
+    }
+
+    #[test]
+    fn test_resume_before_first_click() {
+        for (grid, first_click) in [
+            (Grid::new(9, 9), (4, 4).into()),
+            (Grid::new(5, 5).with_depth(3), (2, 2, 1).into()),
+            (Grid::new(9, 9).with_kernel(Kernel::knight()), (4, 4).into()),
+            (Grid::new(9, 9).with_mask(|pos| pos.x() != 0), (4, 4).into()),
+        ] {
+            let mut game = MineField::seeded_on(grid, 70, 3);
+            let mut loaded = MineField::from_bytes(&game.to_bytes().unwrap()).unwrap();
+
+            // so many mines that the first click most likely hits one, which must move to the same cell in both games
+            game.play(Action::Open(first_click));
+            loaded.play(Action::Open(first_click));
+
+            assert_eq!(loaded.to_text(), game.to_text());
+            assert_eq!(loaded.grid(), game.grid());
+        }
+    }
+
+    #[test]
+    fn test_save_after_first_click_moved_a_mine() {
+        let mut game = MineField::seeded(5, 5, 20, 1);
+        game.play(Action::Open((2, 2).into()));
+        assert!(game.history()[0].relocation.is_some());
+
+        let board = game.starting_board();
+        assert_eq!(board.seed(), None);
+        let mut loaded = MineField::from_json(&board.to_json().unwrap()).unwrap();
+        loaded.play(Action::Open((2, 2).into()));
+        assert_eq!(loaded.to_text(), game.to_text());
+    }
+
+    #[test]
+    fn test_load_invalid_save() {
+        let game: MineField = "1*.\n".parse().unwrap();
+        let json = game.to_json().unwrap();
+
+        assert!(MineField::from_json(&json).is_ok());
+        assert!(MineField::from_json(&json.replace("\"width\":3", "\"width\":4")).is_err());
+        assert!(MineField::from_json(&json.replace("\"mines\":[1]", "\"mines\":[0]")).is_err());
+        assert!(MineField::from_json(&json.replace("\"Ongoing\"", "\"Won\"")).is_err());
+    }
+}
@@ -1,14 +1,61 @@
+mod action;
 mod cell;
+mod error;
+mod grid;
+mod history;
+mod kernel;
 mod minefield;
 mod position;
+mod text;
+mod view;
 
-#[derive(PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GameState {
     Ongoing,
     Won,
     Lost,
 }
 
+/// rule deciding when a game is won
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WinCondition {
+    /// every cell without a mine has been opened
+    #[default]
+    OpenAllSafe,
+    /// every mine has been flagged, and no flag sits on a safe cell
+    FlagAllMines,
+}
+
+/// `open-all-safe` or `flag-all-mines`
+impl std::str::FromStr for WinCondition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "open-all-safe" => Ok(WinCondition::OpenAllSafe),
+            "flag-all-mines" => Ok(WinCondition::FlagAllMines),
+            _ => Err(format!("Unknown win condition {s:?}")),
+        }
+    }
+}
+
+impl std::fmt::Display for WinCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            WinCondition::OpenAllSafe => "open-all-safe",
+            WinCondition::FlagAllMines => "flag-all-mines",
+        })
+    }
+}
+
+pub use action::{Action, Move};
 pub use cell::{CellConfig, CellData};
+pub use error::GameError;
+pub use grid::{Grid, Tiling, Topology};
+pub use history::HistoryEntry;
+pub use kernel::Kernel;
 pub use minefield::MineField;
 pub use position::Position;
+pub use view::PlayerView;
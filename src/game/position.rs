@@ -1,16 +1,24 @@
 use std::fmt::{Debug, Display};
 
-use crate::MineField;
+use crate::Grid;
 
 #[derive(PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Position {
     x: usize,
     y: usize,
+    /// layer of a three-dimensional board, 0 on a flat one
+    #[cfg_attr(feature = "serde", serde(default))]
+    z: usize,
 }
 
+/// the layer is only written when it is not the first one, so that positions of flat boards read as usual
 impl Display for Position {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("({}, {})", self.x, self.y))
+        match self.z {
+            0 => f.write_fmt(format_args!("({}, {})", self.x, self.y)),
+            z => f.write_fmt(format_args!("({}, {}, {z})", self.x, self.y)),
+        }
     }
 }
 
@@ -20,65 +28,42 @@ impl Debug for Position {
     }
 }
 
-impl Into<Position> for (usize, usize) {
-    fn into(self) -> Position {
-        Position::from(self.0, self.1)
+impl From<(usize, usize)> for Position {
+    fn from((x, y): (usize, usize)) -> Self {
+        Position::from(x, y)
+    }
+}
+
+impl From<(usize, usize, usize)> for Position {
+    fn from((x, y, z): (usize, usize, usize)) -> Self {
+        Self { x, y, z }
     }
 }
 
 impl Position {
     pub fn from(x: usize, y: usize) -> Self {
-        Self { x, y }
+        Self { x, y, z: 0 }
     }
 
-    pub(super) fn random(width: usize, height: usize) -> Self {
-        Self {
-            x: rand::random_range(0..width),
-            y: rand::random_range(0..height),
-        }
+    pub fn x(self) -> usize {
+        self.x
     }
 
-    pub fn to_index(self, width: usize) -> usize {
-        self.x + self.y * width
+    pub fn y(self) -> usize {
+        self.y
     }
 
-    pub fn neighbours(self, context: &MineField) -> Vec<Position> {
-        let mut neigbhours = Vec::new();
-
-        let (x, y) = (self.x, self.y);
-
-        if x > 0 && y > 0 {
-            neigbhours.push((x - 1, y - 1).into());
-        }
-
-        if y > 0 {
-            neigbhours.push((x, y - 1).into());
-        }
-
-        if x < context.width() - 1 && y > 0 {
-            neigbhours.push((x + 1, y - 1).into());
-        }
-
-        if x > 0 {
-            neigbhours.push((x - 1, y).into());
-        }
-
-        if x < context.width() - 1 {
-            neigbhours.push((x + 1, y).into());
-        }
-
-        if x > 0 && y < context.height() - 1 {
-            neigbhours.push((x - 1, y + 1).into());
-        }
-
-        if y < context.height() - 1 {
-            neigbhours.push((x, y + 1).into());
-        }
+    pub fn z(self) -> usize {
+        self.z
+    }
 
-        if x < context.width() - 1 && y < context.height() - 1 {
-            neigbhours.push((x + 1, y + 1).into());
-        }
+    /// index of the position in the cells of a flat board, row by row, see [`Grid::index`] for any board
+    pub fn to_index(self, width: usize) -> usize {
+        self.x + self.y * width
+    }
 
-        neigbhours
+    /// positions next to this one on the board of `context`
+    pub fn neighbours(self, context: &impl AsRef<Grid>) -> Vec<Position> {
+        context.as_ref().neighbours(self)
     }
 }
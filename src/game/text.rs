@@ -0,0 +1,404 @@
+use std::str::FromStr;
+
+use crate::{
+    CellData, GameError, Grid, MineField, PlayerView, Position,
+    game::cell::{Cell, CellState},
+};
+
+/// a board as plain text, one line per row:
+/// - `.` a closed cell, `*` a closed cell holding a mine
+/// - ` ` or a digit for an open cell, the digit being the number of neighbouring mines
+/// - `F` a flagged mine, `f` a flag on a safe cell
+/// - `?` a question mark on a safe cell, `!` a question mark on a mine
+/// - `X` an open mine, the game being lost
+/// - `#` a hole in the board, see [`Grid::with_mask`]
+///
+/// open cells with more than 9 neighbouring mines, which only boards of several layers or with a kernel reaching
+/// far have, show a letter from `a` for 10, then a capital from `A` for 34, skipping `f`, `x` and their capitals,
+/// then an accented letter from `À` for 58, skipping `×` and `÷`, up to the 146 neighbours a cell may have
+///
+/// every row has as many characters, blanks being checked like digits, empty lines at the end are ignored, and the
+/// layers of a three-dimensional board are separated by a line of `-`
+impl FromStr for MineField {
+    type Err = GameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (grid, characters) = read_rows(s)?;
+
+        let mut field = Vec::with_capacity(grid.len());
+        let mut counts = Vec::new();
+
+        for (pos, character) in grid.all_positions().zip(characters) {
+            let (has_mine, state) = match character {
+                '#' => (false, CellState::Closed),
+                '.' => (false, CellState::Closed),
+                '*' => (true, CellState::Closed),
+                ' ' => {
+                    counts.push((pos, 0));
+                    (false, CellState::Open)
+                }
+                'F' => (true, CellState::Flagged),
+                'f' => (false, CellState::Flagged),
+                '?' => (false, CellState::Questioned),
+                '!' => (true, CellState::Questioned),
+                'X' => (true, CellState::Open),
+                _ => match character_count(character) {
+                    Some(count) => {
+                        counts.push((pos, count));
+                        (false, CellState::Open)
+                    }
+                    None => return Err(GameError::InvalidCharacter { character, pos }),
+                },
+            };
+
+            field.push(Cell { has_mine, state });
+        }
+
+        let game = MineField::from_cells(grid, field)?;
+
+        for (pos, shown) in counts {
+            if let CellData::Open(mines) = game.cell_data(pos)
+                && mines != shown
+            {
+                return Err(GameError::WrongCount { pos, shown, mines });
+            }
+        }
+
+        Ok(game)
+    }
+}
+
+/// the characters of a board and its shape, `#` being holes in it
+fn read_rows(s: &str) -> Result<(Grid, Vec<char>), GameError> {
+    let mut layers: Vec<Vec<Vec<char>>> = vec![Vec::new()];
+    for line in s.trim_end_matches('\n').lines() {
+        if line.starts_with('-') {
+            layers.push(Vec::new());
+        } else if let Some(layer) = layers.last_mut() {
+            layer.push(line.chars().collect());
+        }
+    }
+
+    let height = layers[0].len();
+    if let Some((layer, rows)) = layers
+        .iter()
+        .enumerate()
+        .find(|(_, rows)| rows.len() != height)
+    {
+        return Err(GameError::LayerHeight {
+            layer,
+            rows: rows.len(),
+            expected: height,
+        });
+    }
+
+    let width = layers[0].first().map_or(0, Vec::len);
+    for (layer, rows) in layers.iter().enumerate() {
+        if let Some((row, characters)) = rows
+            .iter()
+            .enumerate()
+            .find(|(_, characters)| characters.len() != width)
+        {
+            return Err(GameError::RowWidth {
+                row: (0, row, layer).into(),
+                characters: characters.len(),
+                expected: width,
+            });
+        }
+    }
+    let grid = Grid::new(width, height).with_depth(layers.len());
+
+    let characters: Vec<char> = grid
+        .all_positions()
+        .map(|pos| layers[pos.z()][pos.y()][pos.x()])
+        .collect();
+
+    let shape = grid.clone();
+    let grid = grid.with_mask(|pos| characters[shape.index(pos)] != '#');
+    Ok((grid, characters))
+}
+
+/// letters standing for 10 mines and more, `f` being a flag
+const LETTERS: &[u8] = b"abcdeghijklmnopqrstuvwyzABCDEGHIJKLMNOPQRSTUVWYZ";
+
+/// characters standing for 10 mines and more, in order, enough of them for the most neighbours a cell may have:
+/// three layers of a kernel reaching three cells away, and the cells right above and below
+fn letters() -> impl Iterator<Item = char> {
+    let accented = ('À'..='ſ').filter(|letter| !matches!(letter, '×' | '÷'));
+    LETTERS.iter().map(|letter| *letter as char).chain(accented)
+}
+
+/// character of an open cell showing `mines`
+pub(super) fn count_character(mines: usize) -> char {
+    match mines {
+        0 => ' ',
+        1..=9 => char::from_digit(mines as u32, 10).unwrap_or('?'),
+        _ => letters().nth(mines - 10).unwrap_or('?'),
+    }
+}
+
+fn character_count(character: char) -> Option<usize> {
+    match character {
+        '0'..='9' => Some(character as usize - '0' as usize),
+        _ => letters()
+            .position(|letter| letter == character)
+            .map(|index| index + 10),
+    }
+}
+
+fn cell_data_character(cell: CellData) -> char {
+    match cell {
+        CellData::Closed => '.',
+        CellData::Open(mines) => count_character(mines),
+        CellData::Flagged => 'F',
+        CellData::Questioned => '?',
+    }
+}
+
+/// `character` for each cell of `grid`, one line per row, `#` for holes and a line of `-` between layers
+fn write_rows(grid: &Grid, mut character: impl FnMut(Position) -> char) -> String {
+    let mut text = String::with_capacity((grid.width() + 1) * (grid.height() + 1) * grid.depth());
+
+    for pos in grid.all_positions() {
+        if pos.x() == 0 && pos.y() == 0 && pos.z() > 0 {
+            text.push_str(&"-".repeat(grid.width()));
+            text.push('\n');
+        }
+        text.push(match grid.is_masked(pos) {
+            true => '#',
+            false => character(pos),
+        });
+        if pos.x() + 1 == grid.width() {
+            text.push('\n');
+        }
+    }
+
+    text
+}
+
+impl MineField {
+    /// the board as text, mines included, see [`MineField::from_str`] for the format
+    pub fn to_text(&self) -> String {
+        write_rows(self.grid(), |pos| {
+            let cell = self.get_cell(pos);
+            match (&cell.state, cell.has_mine) {
+                (CellState::Closed, false) => '.',
+                (CellState::Closed, true) => '*',
+                (CellState::Flagged, false) => 'f',
+                (CellState::Flagged, true) => 'F',
+                (CellState::Questioned, false) => '?',
+                (CellState::Questioned, true) => '!',
+                (CellState::Open, true) => 'X',
+                (CellState::Open, false) => cell_data_character(self.cell_data(pos)),
+            }
+        })
+    }
+}
+
+impl Grid {
+    /// the shape drawn by `pattern`, in the layout of [`MineField::from_str`]: `#` for the holes and any other
+    /// character for the cells, e.g. a board of any other format
+    pub fn from_pattern(pattern: &str) -> Result<Self, GameError> {
+        read_rows(pattern).map(|(grid, _)| grid)
+    }
+}
+
+impl PlayerView {
+    /// read a board whose mines are unknown, in the format of [`MineField::from_str`] without the characters giving
+    /// away mines: flags are all `F` and question marks all `?`, `f` still being read as a flag
+    pub fn from_text(text: &str, mines: usize) -> Result<Self, GameError> {
+        let (grid, characters) = read_rows(text)?;
+
+        let cells = grid
+            .all_positions()
+            .zip(characters)
+            .map(|(pos, character)| match character {
+                '.' | '#' => Ok(CellData::Closed),
+                ' ' => Ok(CellData::Open(0)),
+                'F' | 'f' => Ok(CellData::Flagged),
+                '?' => Ok(CellData::Questioned),
+                _ => character_count(character)
+                    .map(CellData::Open)
+                    .ok_or(GameError::InvalidCharacter { character, pos }),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Self::new(grid, mines, cells)
+    }
+
+    /// the board as text, see [`PlayerView::from_text`] for the format
+    pub fn to_text(&self) -> String {
+        write_rows(self.grid(), |pos| cell_data_character(self.cell_data(pos)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Action, GameState, Kernel};
+
+    #[test]
+    fn test_text_round_trip() {
+        let text = "\
+.*1 
+F221
+.f?!
+";
+        let game: MineField = text.parse().unwrap();
+
+        assert_eq!((game.width(), game.height()), (4, 3));
+        assert_eq!(game.mines(), 3);
+        assert_eq!(game.cell_data((0, 1).into()), CellData::Flagged);
+        assert_eq!(game.cell_data((1, 1).into()), CellData::Open(2));
+        assert_eq!(game.to_text(), text);
+    }
+
+    #[test]
+    fn test_text_layers() {
+        let text = "\
+*1
+11
+--
+11
+11
+";
+        let game: MineField = text.parse().unwrap();
+
+        assert_eq!(game.grid().depth(), 2);
+        assert_eq!(game.cell_data((1, 1, 1).into()), CellData::Open(1));
+        assert_eq!(game.to_text(), text);
+
+        let text = "***\n***\n***\n---\n*..\n.a.\n...\n---\n...\n...\n...\n";
+        let game: MineField = text.parse().unwrap();
+        assert_eq!(game.cell_data((1, 1, 1).into()), CellData::Open(10));
+        assert_eq!(game.to_text(), text);
+
+        assert_eq!(
+            "1*\n--\n1*\n11\n".parse::<MineField>().err(),
+            Some(GameError::LayerHeight {
+                layer: 1,
+                rows: 2,
+                expected: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_count_characters() {
+        for count in 1..=146 {
+            assert_eq!(character_count(count_character(count)), Some(count));
+        }
+        assert_eq!(count_character(33), 'z');
+        assert_eq!(count_character(48), 'P');
+        assert_eq!(count_character(58), 'À');
+    }
+
+    #[test]
+    fn test_text_shows_the_largest_counts() {
+        let grid = Grid::new(7, 7).with_depth(3).with_kernel(Kernel::radius(3));
+        let mut game = MineField::seeded_on(grid, 146, 0);
+        game.play(Action::Open((3, 3, 1).into()));
+        assert_eq!(game.cell_data((3, 3, 1).into()), CellData::Open(146));
+
+        let text = game.view().to_text();
+        let row = text.lines().nth(7 + 1 + 3).unwrap();
+        assert_eq!(row.chars().nth(3).and_then(character_count), Some(146));
+    }
+
+    #[test]
+    fn test_text_checks_rows() {
+        let game: MineField = "1*1\n111\n\n".parse().unwrap();
+        assert_eq!(game.to_text(), "1*1\n111\n");
+
+        assert_eq!(
+            "1*1\n11\n".parse::<MineField>().err(),
+            Some(GameError::RowWidth {
+                row: (0, 1).into(),
+                characters: 2,
+                expected: 3
+            })
+        );
+        assert_eq!(
+            " *.\n".parse::<MineField>().err(),
+            Some(GameError::WrongCount {
+                pos: (0, 0).into(),
+                shown: 0,
+                mines: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_text_holes() {
+        let text = "\
+#1*1
+##11
+#...
+";
+        let game: MineField = text.parse().unwrap();
+
+        assert_eq!(game.grid().cell_count(), 8);
+        assert!(game.grid().is_masked((1, 1).into()));
+        assert_eq!(game.cell_data((2, 1).into()), CellData::Open(1));
+        assert_eq!(game.to_text(), text);
+        assert_eq!(game.view().to_text(), text.replace('*', "."));
+        assert_eq!(
+            PlayerView::from_text(&game.view().to_text(), 1).unwrap(),
+            game.view()
+        );
+
+        let grid = Grid::from_pattern("#.#\n...\n").unwrap();
+        assert_eq!((grid.width(), grid.height(), grid.cell_count()), (3, 2, 4));
+    }
+
+    #[test]
+    fn test_text_keeps_the_state_of_the_game() {
+        let mut game: MineField = "1*.\n".parse().unwrap();
+        // a cell is open, so the first click was played and mines no longer move
+        assert_eq!(game.play(Action::Open((1, 0).into())), GameState::Lost);
+        assert_eq!(game.to_text(), "1X.\n");
+
+        let game: MineField = "1X.\n".parse().unwrap();
+        assert_eq!(game.state(), GameState::Lost);
+
+        let game: MineField = "1*1\n".parse().unwrap();
+        assert_eq!(game.state(), GameState::Won);
+    }
+
+    #[test]
+    fn test_view_from_text() {
+        let game: MineField = ".*1 \nF221\n.f?!\n".parse().unwrap();
+        let view = PlayerView::from_text("..1 \nF221\n.f??\n", 3).unwrap();
+
+        assert_eq!(view.to_text(), game.view().to_text());
+        assert_eq!(view.cell_data((1, 1).into()), CellData::Open(2));
+        assert_eq!(view.remaining_mines(), 1);
+        assert_eq!(
+            PlayerView::from_text("1*\n", 1).err(),
+            Some(GameError::InvalidCharacter {
+                character: '*',
+                pos: (1, 0).into()
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_text() {
+        assert_eq!("".parse::<MineField>().err(), Some(GameError::EmptyBoard));
+        assert_eq!(
+            "1*\n.x\n".parse::<MineField>().err(),
+            Some(GameError::InvalidCharacter {
+                character: 'x',
+                pos: (1, 1).into()
+            })
+        );
+        assert_eq!(
+            "2*.\n".parse::<MineField>().err(),
+            Some(GameError::WrongCount {
+                pos: (0, 0).into(),
+                shown: 2,
+                mines: 1
+            })
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use crate::{CellData, GameError, Grid, Position};
+
+/// everything a player can see of a game, and nothing of where its mines are
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "UncheckedView")
+)]
+pub struct PlayerView {
+    pub(super) grid: Grid,
+    pub(super) mines: usize,
+    pub(super) cells: Vec<CellData>,
+}
+
+impl PlayerView {
+    /// a board as seen by a player, whose mines may be unknown, e.g. copied from a real game
+    pub fn new(grid: Grid, mines: usize, cells: Vec<CellData>) -> Result<Self, GameError> {
+        if cells.len() != grid.len() {
+            return Err(GameError::CellCount {
+                cells: cells.len(),
+                expected: grid.len(),
+            });
+        }
+
+        if mines > grid.cell_count() {
+            return Err(GameError::TooManyMines {
+                mines,
+                cells: grid.cell_count(),
+            });
+        }
+
+        Ok(Self { grid, mines, cells })
+    }
+
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    pub fn width(&self) -> usize {
+        self.grid.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.grid.height()
+    }
+
+    /// total number of mines hidden in the field
+    pub fn mines(&self) -> usize {
+        self.mines
+    }
+
+    /// number of mines minus the number of flags placed, as a player would count them
+    pub fn remaining_mines(&self) -> isize {
+        let flags = self
+            .cells
+            .iter()
+            .filter(|cell| **cell == CellData::Flagged)
+            .count();
+        self.mines as isize - flags as isize
+    }
+
+    pub fn contains(&self, pos: Position) -> bool {
+        self.grid.contains(pos)
+    }
+
+    pub fn try_cell_data(&self, pos: Position) -> Result<CellData, GameError> {
+        if self.contains(pos) {
+            Ok(self.cell_data(pos))
+        } else {
+            Err(GameError::OutOfBounds(pos))
+        }
+    }
+
+    pub fn cell_data(&self, pos: Position) -> CellData {
+        self.cells[self.grid.index(pos)]
+    }
+}
+
+impl AsRef<Grid> for PlayerView {
+    fn as_ref(&self) -> &Grid {
+        &self.grid
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct UncheckedView {
+    grid: Grid,
+    mines: usize,
+    cells: Vec<CellData>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<UncheckedView> for PlayerView {
+    type Error = GameError;
+
+    fn try_from(view: UncheckedView) -> Result<Self, Self::Error> {
+        Self::new(view.grid, view.mines, view.cells)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl PlayerView {
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CellConfig, MineField};
+
+    #[test]
+    fn test_view_hides_mines() {
+        let game = MineField::from([[CellConfig::Open, CellConfig::Mine, CellConfig::Flagged]]);
+        let view = game.view();
+
+        assert_eq!(view.grid(), &Grid::new(3, 1));
+        assert_eq!(view.mines(), 2);
+        assert_eq!(view.remaining_mines(), 1);
+        assert_eq!(view.cell_data((1, 0).into()), CellData::Closed);
+        assert_eq!(
+            PlayerView::new(Grid::new(2, 2), 1, vec![CellData::Closed; 3]),
+            Err(GameError::CellCount {
+                cells: 3,
+                expected: 4
+            })
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_view_json() {
+        let view = MineField::from([[CellConfig::Open, CellConfig::Mine]]).view();
+        let json = view.to_json().unwrap();
+
+        assert_eq!(PlayerView::from_json(&json).unwrap(), view);
+        assert!(PlayerView::from_json(&json.replace("\"mines\":1", "\"mines\":3")).is_err());
+    }
+}
@@ -0,0 +1,157 @@
+use std::time::{Duration, Instant};
+
+use crate::{Action, GameError, GameState, Grid, MineField, Position, Strategy, StrategyChain};
+
+/// generates boards which can be solved from a given first click without ever guessing
+pub struct NoGuessGenerator {
+    grid: Grid,
+    mines: u32,
+    first_click: Position,
+    attempts: usize,
+    solver: StrategyChain,
+}
+
+/// a board solvable without guessing, along with how it was found
+pub struct Generated {
+    /// the board, as it was before the first click
+    pub game: MineField,
+    /// seed of the board, see [`MineField::seeded`]
+    pub seed: u64,
+    /// number of boards generated until one of them could be solved
+    pub attempts: usize,
+    pub duration: Duration,
+}
+
+impl NoGuessGenerator {
+    /// boards of the shape of `grid` with as many mines, to be started by opening `first_click`
+    pub fn new(grid: Grid, mines: u32, first_click: Position) -> Self {
+        Self {
+            grid,
+            mines,
+            first_click,
+            attempts: 1000,
+            solver: StrategyChain::deterministic(),
+        }
+    }
+
+    /// number of boards to try before giving up, 1000 by default
+    pub fn with_attempts(mut self, attempts: usize) -> Self {
+        self.attempts = attempts;
+        self
+    }
+
+    /// stages the boards must be solvable with, [`StrategyChain::deterministic`] by default
+    ///
+    /// # Panics
+    /// when one of the stages may guess
+    pub fn with_solver(mut self, solver: StrategyChain) -> Self {
+        assert!(
+            solver.is_deterministic(),
+            "A board solved by guessing is no proof that it needs no guess"
+        );
+        self.solver = solver;
+        self
+    }
+
+    /// try the boards seeded with `seed`, `seed + 1` and so on, until one of them is solved by opening the first
+    /// click and then playing the moves of the solver
+    ///
+    /// the first click moves a mine out of the way as it does on any board, and since it does so the same way for
+    /// the same seed, the board returned is solvable from the first click as well
+    pub fn generate(&self, seed: u64) -> Result<Generated, GameError> {
+        if !self.grid.contains(self.first_click) {
+            return Err(GameError::OutOfBounds(self.first_click));
+        }
+
+        let start = Instant::now();
+
+        for attempt in 0..self.attempts {
+            let seed = seed.wrapping_add(attempt as u64);
+            let mut game = MineField::try_seeded_on(self.grid.clone(), self.mines, seed)?;
+
+            let mut state = game.try_play(Action::Open(self.first_click))?;
+            while state == GameState::Ongoing {
+                let Some(next_move) = self.solver.next_move(&game.view()) else {
+                    break;
+                };
+                state = game.try_play(next_move)?;
+            }
+
+            if state == GameState::Won {
+                let duration = start.elapsed();
+                log::debug!(
+                    seed,
+                    attempts = attempt + 1,
+                    duration_ms = duration.as_millis() as u64;
+                    "Generated a board without guessing"
+                );
+                return Ok(Generated {
+                    game: MineField::try_seeded_on(self.grid.clone(), self.mines, seed)?,
+                    seed,
+                    attempts: attempt + 1,
+                    duration,
+                });
+            }
+            log::trace!(seed, state:? = state; "Board needs guessing");
+        }
+
+        Err(GameError::NoGuessBoardNotFound {
+            attempts: self.attempts,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Tiling, Topology};
+
+    #[test]
+    fn test_generated_board_is_solvable_from_the_first_click() {
+        let first_click: Position = (4, 4).into();
+        let generated = NoGuessGenerator::new(Grid::new(9, 9), 10, first_click)
+            .generate(0)
+            .unwrap();
+        assert!(generated.attempts >= 1);
+
+        let solver = StrategyChain::deterministic();
+        let mut game = generated.game;
+        game.play(Action::Open(first_click));
+        while let Some(next_move) = solver.next_move(&game.view()) {
+            game.play(next_move);
+        }
+        assert_eq!(game.state(), GameState::Won);
+
+        let again = NoGuessGenerator::new(Grid::new(9, 9), 10, first_click)
+            .generate(0)
+            .unwrap();
+        assert_eq!(again.seed, generated.seed);
+    }
+
+    #[test]
+    fn test_attempt_budget() {
+        // with three cells out of four holding a mine, the first click hardly ever opens more than itself
+        let generator = NoGuessGenerator::new(Grid::new(8, 8), 48, (0, 0).into()).with_attempts(3);
+        assert_eq!(
+            generator.generate(0).err(),
+            Some(GameError::NoGuessBoardNotFound { attempts: 3 })
+        );
+
+        let generator = NoGuessGenerator::new(Grid::new(8, 8), 10, (8, 0).into());
+        assert_eq!(
+            generator.generate(0).err(),
+            Some(GameError::OutOfBounds((8, 0).into()))
+        );
+    }
+
+    #[test]
+    fn test_hexagonal_torus() {
+        let grid = Grid::new(10, 10)
+            .with_tiling(Tiling::Hexagonal)
+            .with_topology(Topology::Torus);
+        let generated = NoGuessGenerator::new(grid.clone(), 15, (5, 5).into())
+            .generate(0)
+            .unwrap();
+        assert_eq!(generated.game.grid(), &grid);
+    }
+}
@@ -1,5 +1,11 @@
+pub mod bench;
 mod game;
+pub mod generator;
+mod logging;
+pub mod replay;
 mod solver;
+pub mod tui;
 
 pub use game::*;
+pub use logging::PrintLogger;
 pub use solver::*;
@@ -0,0 +1,47 @@
+use log::{LevelFilter, Log, Metadata, Record, SetLoggerError, kv};
+
+/// prints the events of this crate to stderr, away from the output of the commands, as messages followed by their
+/// structured fields
+/// the solvers are silent unless a logger such as this one is installed
+pub struct PrintLogger;
+
+static LOGGER: PrintLogger = PrintLogger;
+
+impl PrintLogger {
+    /// install the logger, printing events up to `level`
+    pub fn init(level: LevelFilter) -> Result<(), SetLoggerError> {
+        log::set_logger(&LOGGER)?;
+        log::set_max_level(level);
+        Ok(())
+    }
+}
+
+struct Fields(Vec<String>);
+
+impl<'kvs> kv::VisitSource<'kvs> for Fields {
+    fn visit_pair(&mut self, key: kv::Key<'kvs>, value: kv::Value<'kvs>) -> Result<(), kv::Error> {
+        self.0.push(format!("{key}={value}"));
+        Ok(())
+    }
+}
+
+impl Log for PrintLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level() && metadata.target().starts_with("minesweeper")
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let mut fields = Fields(Vec::new());
+        if record.key_values().visit(&mut fields).is_err() || fields.0.is_empty() {
+            eprintln!("{}", record.args());
+        } else {
+            eprintln!("{} {{{}}}", record.args(), fields.0.join(", "));
+        }
+    }
+
+    fn flush(&self) {}
+}
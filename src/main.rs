@@ -1,29 +1,496 @@
-use minesweeper::{GameState, MineField, find_next_move};
+use std::{
+    io::BufRead,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
 
-fn main() {
-    println!("Hello, world!");
+use clap::{Args, Parser, Subcommand, ValueEnum, builder::RangedU64ValueParser};
+use log::LevelFilter;
+use minesweeper::{
+    Action, GameState, Grid, Kernel, MineField, Move, PlayerView, Position, PrintLogger, Strategy,
+    StrategyChain, Tiling, Topology,
+    bench::{self, BoardConfig, Csv, Json, Table},
+    generator::NoGuessGenerator,
+    replay::{Recorder, Replay, ReplayMove, ReplayPlayer},
+    tui,
+};
 
-    let width = 50;
-    let height = 50;
-    let mine_rate = 0.12;
-    let mines = (mine_rate * ((width * height) as f64)) as u32;
+#[derive(Parser)]
+#[command(version, about = "Minesweeper, played by hand or by the solver")]
+struct Cli {
+    /// log what the solver does, twice for every detail
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
+    verbose: u8,
 
-    let mut game = MineField::new(width, height, mines);
+    /// only print the results, not the boards
+    #[arg(short, long, global = true)]
+    quiet: bool,
 
-    while let Some((pos, flag)) = find_next_move(&game) {
-        match game.play(pos, flag) {
-            GameState::Ongoing => {}
-            GameState::Won => {
-                println!("{}", game);
-                println!("WON!");
-                return;
+    #[arg(long, value_enum, default_value_t = Format::Text, global = true)]
+    format: Format,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// play a board by hand in the terminal
+    Play {
+        #[command(flatten)]
+        board: BoardArgs,
+        /// directory in which to save the replay of the game, as <seed>.replay
+        #[arg(long)]
+        record: Option<PathBuf>,
+    },
+    /// let the solver play boards
+    Solve {
+        #[command(flatten)]
+        board: BoardArgs,
+        #[command(flatten)]
+        solver: SolverArgs,
+        /// number of games, seeded one after the other from the seed
+        #[arg(long, default_value_t = 1, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+        games: usize,
+        /// directory in which to save the replay of every game, as <seed>.replay
+        #[arg(long)]
+        record: Option<PathBuf>,
+    },
+    /// play a recorded game again, move by move
+    Replay {
+        file: PathBuf,
+        /// milliseconds between two moves (default: as long as when they were recorded)
+        #[arg(long, conflicts_with = "step")]
+        delay: Option<u64>,
+        /// wait for enter before each move, `b` stepping back and `q` quitting
+        #[arg(long)]
+        step: bool,
+    },
+    /// measure how well the solver does on many boards
+    Bench {
+        /// beginner, intermediate, expert, WxH:MINES or WxH@DENSITY (default: the three presets)
+        boards: Vec<BoardConfig>,
+        #[command(flatten)]
+        solver: SolverArgs,
+        /// number of games per board
+        #[arg(long, default_value_t = 100, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+        games: usize,
+        /// seed of the first game of each board, the next ones being seeded one after the other
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// number of games played at once (default: one per core)
+        #[arg(long)]
+        threads: Option<usize>,
+    },
+    /// show what the solver would play on a board read from a file
+    Analyze {
+        file: PathBuf,
+        /// total number of mines, for a board whose mines are unknown, which is then read without them
+        #[arg(long)]
+        mines: Option<usize>,
+        #[command(flatten)]
+        solver: SolverArgs,
+    },
+}
+
+#[derive(Args)]
+struct BoardArgs {
+    /// difficulty preset, instead of a size and a number of mines
+    #[arg(long, value_enum, conflicts_with_all = ["width", "height", "mines", "density"])]
+    preset: Option<Preset>,
+    #[arg(long, default_value_t = 50)]
+    width: usize,
+    #[arg(long, default_value_t = 50)]
+    height: usize,
+    /// number of layers stacked on top of each other, each cell touching the ones above and below it
+    #[arg(long, default_value_t = 1, conflicts_with = "preset")]
+    depth: usize,
+    #[arg(long, conflicts_with = "density")]
+    mines: Option<u32>,
+    /// share of the cells holding a mine
+    #[arg(long, default_value_t = 0.12)]
+    density: f64,
+    /// seed of the board (default: random)
+    #[arg(long)]
+    seed: Option<u64>,
+    /// bounded, wrap-horizontally, wrap-vertically or torus
+    #[arg(long, default_value_t = Topology::Bounded)]
+    topology: Topology,
+    /// square or hexagonal
+    #[arg(long, default_value_t = Tiling::Square)]
+    tiling: Tiling,
+    /// cells counted as neighbours: ring, cross, knight, radius-2, radius-3, or offsets such as "2,0 -2,0"
+    /// (default: the cells touching each cell)
+    #[arg(long)]
+    kernel: Option<Kernel>,
+    /// file drawing the shape of the board, `#` for holes and any other character for a cell, instead of a size
+    #[arg(long, conflicts_with_all = ["preset", "width", "height", "depth"])]
+    mask: Option<PathBuf>,
+    /// only play boards which can be solved without guessing, starting from the middle of the board
+    #[arg(long)]
+    no_guess: bool,
+    /// boards to generate before giving up on finding one which needs no guess
+    #[arg(long, default_value_t = 1000, requires = "no_guess")]
+    attempts: usize,
+}
+
+impl BoardArgs {
+    fn config(&self) -> BoardConfig {
+        match (self.preset, self.mines) {
+            (Some(Preset::Beginner), _) => BoardConfig::beginner(),
+            (Some(Preset::Intermediate), _) => BoardConfig::intermediate(),
+            (Some(Preset::Expert), _) => BoardConfig::expert(),
+            (None, Some(mines)) => BoardConfig::new(self.width, self.height, mines),
+            (None, None) => BoardConfig::with_density(self.width, self.height, self.density),
+        }
+    }
+
+    /// the cell to open first on a board generated without guessing, where the cursor of [`tui::play`] starts
+    fn first_click(grid: &Grid) -> Position {
+        grid.centre().unwrap_or((0, 0).into())
+    }
+
+    /// the board seeded with `seed`, or with `--no-guess` the first one from it which needs no guess, along with the
+    /// seed it ended up with
+    fn game(&self, cli: &Cli, seed: u64) -> color_eyre::Result<(MineField, u64)> {
+        let config = self.config();
+        let grid = match &self.mask {
+            Some(file) => Grid::from_pattern(&std::fs::read_to_string(file)?)?,
+            None => Grid::new(config.width, config.height).with_depth(self.depth),
+        };
+        let mut grid = grid.with_topology(self.topology).with_tiling(self.tiling);
+        if let Some(kernel) = self.kernel {
+            grid = grid.with_kernel(kernel);
+        }
+        let mines = match (self.preset, self.mines) {
+            // the share is of the cells of every layer, holes left out
+            (None, None) => (self.density * grid.cell_count() as f64) as u32,
+            _ => config.mines,
+        };
+        if !self.no_guess {
+            return Ok((MineField::try_seeded_on(grid, mines, seed)?, seed));
+        }
+
+        let first_click = Self::first_click(&grid);
+        let generated = NoGuessGenerator::new(grid, mines, first_click)
+            .with_attempts(self.attempts)
+            .generate(seed)?;
+        if !cli.quiet {
+            eprintln!(
+                "Generated a board without guessing in {:.1} ms ({} boards tried)",
+                generated.duration.as_secs_f64() * 1000.,
+                generated.attempts
+            );
+        }
+        Ok((generated.game, generated.seed))
+    }
+}
+
+#[derive(Args)]
+struct SolverArgs {
+    /// comma separated stages of the solver, tried in order
+    #[arg(
+        long,
+        default_value = "immediate,invariant,counting,multi-cluster,probability"
+    )]
+    strategies: String,
+}
+
+impl SolverArgs {
+    fn solver(&self) -> color_eyre::Result<StrategyChain> {
+        self.strategies.parse().map_err(eyre::Error::msg)
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Preset {
+    Beginner,
+    Intermediate,
+    Expert,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum Format {
+    Text,
+    Csv,
+    Json,
+}
+
+fn main() -> color_eyre::Result<()> {
+    color_eyre::install()?;
+    let cli = Cli::parse();
+
+    let level = match cli.verbose {
+        0 => None,
+        1 => Some(LevelFilter::Debug),
+        _ => Some(LevelFilter::Trace),
+    };
+    if let Some(level) = level {
+        PrintLogger::init(level).expect("No other logger is installed");
+    }
+
+    match &cli.command {
+        Command::Play { board, record } => play(&cli, board, record.as_deref()),
+        Command::Solve {
+            board,
+            solver,
+            games,
+            record,
+        } => solve(&cli, board, &solver.solver()?, *games, record.as_deref()),
+        Command::Bench {
+            boards,
+            solver,
+            games,
+            seed,
+            threads,
+        } => {
+            solver.solver()?;
+            let boards = if boards.is_empty() {
+                vec![
+                    BoardConfig::beginner(),
+                    BoardConfig::intermediate(),
+                    BoardConfig::expert(),
+                ]
+            } else {
+                boards.clone()
+            };
+            let threads = threads
+                .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, usize::from));
+
+            let reports = boards
+                .iter()
+                .map(|config| {
+                    bench::run(config, *games, *seed, threads, || {
+                        solver.solver().expect("Strategies were checked")
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            match cli.format {
+                Format::Text => print!("{}", Table(&reports)),
+                Format::Csv => print!("{}", Csv(&reports)),
+                Format::Json => print!("{}", Json(&reports)),
             }
-            GameState::Lost => {
-                println!("{}", game);
-                println!("LOST!");
-                return;
+            Ok(())
+        }
+        Command::Analyze {
+            file,
+            mines,
+            solver,
+        } => analyze(&cli, file, *mines, &solver.solver()?),
+        Command::Replay { file, delay, step } => {
+            replay(&cli, file, delay.map(Duration::from_millis), *step)
+        }
+    }
+}
+
+fn analyze(
+    cli: &Cli,
+    file: &Path,
+    mines: Option<usize>,
+    solver: &StrategyChain,
+) -> color_eyre::Result<()> {
+    let text = std::fs::read_to_string(file)?;
+
+    let view = match mines {
+        Some(mines) => PlayerView::from_text(&text, mines)?,
+        None => {
+            let game: MineField = text.parse()?;
+            if !cli.quiet {
+                println!("{game}");
+            }
+            if game.state() != GameState::Ongoing {
+                print_state(game.state());
+                return Ok(());
             }
+            game.view()
+        }
+    };
+
+    if mines.is_some() && !cli.quiet {
+        print!("{}", view.to_text());
+    }
+    println!("Mines left: {}", view.remaining_mines());
+
+    match solver.hint(&view) {
+        Some(hint) => println!("[{}] {hint}", hint.strategy),
+        None => println!("No move found"),
+    }
+
+    Ok(())
+}
+
+fn play(cli: &Cli, board: &BoardArgs, record: Option<&Path>) -> color_eyre::Result<()> {
+    let (game, seed) = board.game(cli, board.seed.unwrap_or_else(rand::random))?;
+
+    let (game, replay) = tui::play(game)?;
+    if let Some(directory) = record {
+        save_replay(directory, &replay)?;
+    }
+
+    println!("Seed: {seed}");
+    if !cli.quiet {
+        println!("{game}");
+    }
+    if game.state() != GameState::Ongoing {
+        print_state(game.state());
+    }
+
+    Ok(())
+}
+
+fn solve(
+    cli: &Cli,
+    board: &BoardArgs,
+    solver: &StrategyChain,
+    games: usize,
+    record: Option<&Path>,
+) -> color_eyre::Result<()> {
+    let mut seed = board.seed.unwrap_or_else(rand::random);
+
+    match cli.format {
+        Format::Text => {}
+        Format::Csv => println!("seed,state,moves,time_ms"),
+        Format::Json => print!("["),
+    }
+
+    for index in 0..games {
+        let (mut game, game_seed) = board.game(cli, seed)?;
+        // the next board is looked for after this one, so that --no-guess does not find the same board twice
+        seed = game_seed.wrapping_add(1);
+        let seed = game_seed;
+
+        let start = Instant::now();
+        let mut recorder = Recorder::new();
+        let mut moves = 0;
+        let mut state = GameState::Ongoing;
+        if board.no_guess {
+            // the board only needs no guess from this cell
+            let first_click = Move::new(Action::Open(BoardArgs::first_click(game.grid())))
+                .with_strategy("first-click");
+            moves += 1;
+            state = recorder.play(&mut game, first_click)?;
+        }
+        while state == GameState::Ongoing
+            && let Some(next_move) = solver.next_move(&game.view())
+        {
+            moves += 1;
+            state = recorder.play(&mut game, next_move)?;
+        }
+        let time_ms = start.elapsed().as_secs_f64() * 1000.;
+
+        if let Some(directory) = record {
+            save_replay(directory, &recorder.finish(&game))?;
+        }
+
+        let state = match game.state() {
+            GameState::Ongoing => "stuck",
+            GameState::Won => "won",
+            GameState::Lost => "lost",
+        };
+
+        match cli.format {
+            Format::Text => {
+                println!("Seed: {seed}");
+                if !cli.quiet {
+                    println!("{game}");
+                }
+                print_state(game.state());
+            }
+            Format::Csv => println!("{seed},{state},{moves},{time_ms}"),
+            Format::Json => {
+                if index > 0 {
+                    print!(",");
+                }
+                print!(
+                    "\n  {{\"seed\": {seed}, \"state\": \"{state}\", \"moves\": {moves}, \"time_ms\": {time_ms}}}"
+                );
+            }
+        }
+    }
+
+    if cli.format == Format::Json {
+        println!("\n]");
+    }
+
+    Ok(())
+}
+
+fn replay(cli: &Cli, file: &Path, delay: Option<Duration>, step: bool) -> color_eyre::Result<()> {
+    let replay: Replay = std::fs::read_to_string(file)?
+        .parse()
+        .map_err(eyre::Error::msg)?;
+    let mut player = replay.player();
+
+    let show = |player: &ReplayPlayer, last: Option<&str>| {
+        if !cli.quiet {
+            // clear the screen, so that the boards replace each other
+            print!("\x1b[2J\x1b[H{}", player.game());
+        }
+        println!("Move {}/{}", player.position(), replay.moves.len());
+        if let Some(last) = last {
+            println!("{last}");
+        }
+    };
+    let describe = |replay_move: &ReplayMove| {
+        format!(
+            "[{}] {}",
+            replay_move.strategy.as_deref().unwrap_or("player"),
+            replay_move.action
+        )
+    };
+
+    if let Some(seed) = replay.seed {
+        println!("Seed: {seed}");
+    }
+    show(&player, None);
+
+    let mut lines = std::io::stdin().lock().lines();
+    let mut previous = Duration::ZERO;
+    loop {
+        let last = if step {
+            println!("enter: next move, b: back, q: quit");
+            match lines.next().transpose()?.as_deref().map(str::trim) {
+                None | Some("q") => break,
+                Some("b") => player
+                    .step_back()
+                    .map(|replay_move| format!("took back {}", describe(replay_move))),
+                Some(_) => player.step()?.map(describe),
+            }
+        } else {
+            let Some(next) = replay.moves.get(player.position()) else {
+                break;
+            };
+            std::thread::sleep(delay.unwrap_or(next.time.saturating_sub(previous)));
+            previous = next.time;
+            player.step()?.map(describe)
         };
+
+        show(&player, last.as_deref());
+    }
+
+    if player.game().state() != GameState::Ongoing {
+        print_state(player.game().state());
+    }
+
+    Ok(())
+}
+
+/// write `replay` into `directory` as `<seed>.replay`
+fn save_replay(directory: &Path, replay: &Replay) -> color_eyre::Result<()> {
+    std::fs::create_dir_all(directory)?;
+    let name = match replay.seed {
+        Some(seed) => format!("{seed}.replay"),
+        None => "game.replay".to_string(),
+    };
+    std::fs::write(directory.join(name), replay.to_string())?;
+    Ok(())
+}
+
+fn print_state(state: GameState) {
+    match state {
+        GameState::Ongoing => println!("STUCK!"),
+        GameState::Won => println!("WON!"),
+        GameState::Lost => println!("LOST!"),
     }
-    // println!("{}", game);
 }
@@ -0,0 +1,405 @@
+use std::{
+    fmt::Display,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    Action, GameError, GameState, Kernel, MineField, Move, Tiling, Topology, WinCondition,
+};
+
+/// one action of a replay
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReplayMove {
+    /// time elapsed between the start of the recording and the action
+    pub time: Duration,
+    pub action: Action,
+    /// name of the strategy which found the action, `None` when a human played it
+    pub strategy: Option<String>,
+}
+
+/// a recorded game, to be played again action by action
+pub struct Replay {
+    pub seed: Option<u64>,
+    /// the board before the first action, with the mine hit by the first click already moved away
+    pub board: MineField,
+    pub moves: Vec<ReplayMove>,
+}
+
+impl Replay {
+    pub fn player(&self) -> ReplayPlayer<'_> {
+        ReplayPlayer {
+            moves: &self.moves,
+            game: self.board.starting_board(),
+            next: 0,
+        }
+    }
+}
+
+/// plain text replay, the board being written as in [`MineField::to_text`]:
+/// ```text
+/// minesweeper replay
+/// seed 42
+/// board 4x2
+/// topology torus
+/// tiling hexagonal
+/// kernel knight
+/// win flag-all-mines
+/// .*..
+/// ....
+/// moves
+/// 0.000 immediate open (2, 1)
+/// 1520.250 - flag (1, 0)
+/// ```
+/// a board of several layers has its depth written after its height, as in `board 4x2x3`, and its layers separated
+/// as in [`MineField::to_text`]; the topology, the tiling, the kernel and the win condition are only written when
+/// they are not the usual ones
+///
+/// each move is given as the milliseconds since the start of the recording, the strategy which found it or `-` for
+/// a human player, and the action
+impl Display for Replay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "minesweeper replay")?;
+        if let Some(seed) = self.seed {
+            writeln!(f, "seed {seed}")?;
+        }
+
+        let grid = self.board.grid();
+        match grid.depth() {
+            1 => writeln!(f, "board {}x{}", grid.width(), grid.height())?,
+            depth => writeln!(f, "board {}x{}x{depth}", grid.width(), grid.height())?,
+        }
+        let topology = grid.topology();
+        if topology != Topology::Bounded {
+            writeln!(f, "topology {topology}")?;
+        }
+        let tiling = grid.tiling();
+        if tiling != Tiling::Square {
+            writeln!(f, "tiling {tiling}")?;
+        }
+        let kernel = grid.kernel();
+        if kernel != tiling.kernel() {
+            writeln!(f, "kernel {kernel}")?;
+        }
+        let win_condition = self.board.win_condition();
+        if win_condition != WinCondition::default() {
+            writeln!(f, "win {win_condition}")?;
+        }
+        f.write_str(&self.board.to_text())?;
+
+        writeln!(f, "moves")?;
+        for replay_move in &self.moves {
+            writeln!(
+                f,
+                "{:.3} {} {}",
+                replay_move.time.as_secs_f64() * 1000.,
+                replay_move.strategy.as_deref().unwrap_or("-"),
+                replay_move.action
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Replay {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines().enumerate().map(|(index, line)| (index + 1, line));
+        let mut next_line = |expected: &str| {
+            lines
+                .next()
+                .ok_or_else(|| format!("Missing {expected} at the end of the replay"))
+        };
+
+        let (_, header) = next_line("header")?;
+        if header != "minesweeper replay" {
+            return Err("Not a minesweeper replay".to_string());
+        }
+
+        let (mut number, mut line) = next_line("board")?;
+        let mut seed = None;
+        if let Some(value) = line.strip_prefix("seed ") {
+            seed = Some(
+                value
+                    .parse()
+                    .map_err(|_| format!("line {number}: invalid seed {value:?}"))?,
+            );
+            (number, line) = next_line("board")?;
+        }
+
+        let size = line
+            .strip_prefix("board ")
+            .and_then(|size| {
+                size.split('x')
+                    .map(|length| length.parse().ok())
+                    .collect::<Option<Vec<usize>>>()
+            })
+            .unwrap_or_default();
+        let (width, height, depth) = match size[..] {
+            [width, height] => (width, height, 1),
+            [width, height, depth] => (width, height, depth),
+            _ => return Err(format!("line {number}: expected the size of the board")),
+        };
+
+        let (mut number, mut line) = next_line("row")?;
+        let mut topology = Topology::Bounded;
+        if let Some(value) = line.strip_prefix("topology ") {
+            topology = value
+                .parse()
+                .map_err(|error| format!("line {number}: {error}"))?;
+            (number, line) = next_line("row")?;
+        }
+        let mut tiling = Tiling::Square;
+        if let Some(value) = line.strip_prefix("tiling ") {
+            tiling = value
+                .parse()
+                .map_err(|error| format!("line {number}: {error}"))?;
+            (number, line) = next_line("row")?;
+        }
+        let mut kernel = None;
+        if let Some(value) = line.strip_prefix("kernel ") {
+            kernel = Some(
+                value
+                    .parse::<Kernel>()
+                    .map_err(|error| format!("line {number}: {error}"))?,
+            );
+            (number, line) = next_line("row")?;
+        }
+        let mut win_condition = WinCondition::default();
+        if let Some(value) = line.strip_prefix("win ") {
+            win_condition = value
+                .parse()
+                .map_err(|error| format!("line {number}: {error}"))?;
+            (number, line) = next_line("row")?;
+        }
+
+        // rows are padded back to the width of the board, in case trailing blanks were dropped, and the layers are
+        // separated by a line
+        let mut board = format!("{line:<width$}\n");
+        for _ in 1..height * depth + depth - 1 {
+            let (_, row) = next_line("row")?;
+            board.push_str(&format!("{row:<width$}\n"));
+        }
+        let mut board = board
+            .parse::<MineField>()
+            .map_err(|error: GameError| format!("line {number}: {error}"))?
+            .with_topology(topology)
+            .with_tiling(tiling)
+            .with_win_condition(win_condition);
+        if let Some(kernel) = kernel {
+            board = board.with_kernel(kernel);
+        }
+
+        let (number, line) = next_line("moves")?;
+        if line != "moves" {
+            return Err(format!("line {number}: expected the moves"));
+        }
+
+        let mut moves = Vec::new();
+        for (number, line) in lines {
+            let invalid = || format!("line {number}: invalid move {line:?}");
+
+            let mut parts = line.splitn(3, ' ');
+            let (Some(time), Some(strategy), Some(action)) =
+                (parts.next(), parts.next(), parts.next())
+            else {
+                return Err(invalid());
+            };
+
+            let time: f64 = time.parse().map_err(|_| invalid())?;
+            moves.push(ReplayMove {
+                time: Duration::try_from_secs_f64(time / 1000.).map_err(|_| invalid())?,
+                action: action.parse().map_err(|_| invalid())?,
+                strategy: (strategy != "-").then(|| strategy.to_string()),
+            });
+        }
+
+        Ok(Self { seed, board, moves })
+    }
+}
+
+/// records the moves played on a game from its first action, along with when they were played and by whom
+pub struct Recorder {
+    start: Instant,
+    moves: Vec<ReplayMove>,
+}
+
+impl Default for Recorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Recorder {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            moves: Vec::new(),
+        }
+    }
+
+    /// play a move on `game` as [`MineField::try_play`] does, recording each action it is made of
+    pub fn play(
+        &mut self,
+        game: &mut MineField,
+        next_move: impl Into<Move>,
+    ) -> Result<GameState, GameError> {
+        let next_move = next_move.into();
+        let strategy = next_move.strategy.map(String::from);
+
+        // actions undone since the last move are not part of the game anymore
+        let played = game.history().len();
+        self.moves.truncate(played);
+
+        let result = game.try_play(next_move);
+
+        let time = self.start.elapsed();
+        for entry in &game.history()[played..] {
+            self.moves.push(ReplayMove {
+                time,
+                action: entry.action.clone(),
+                strategy: strategy.clone(),
+            });
+        }
+
+        result
+    }
+
+    /// the replay of `game`, as it stands after the recorded moves and the ones undone or redone since
+    pub fn finish(mut self, game: &MineField) -> Replay {
+        self.moves.truncate(game.history().len());
+
+        let board = game.starting_board();
+        Replay {
+            seed: board.seed(),
+            board,
+            moves: self.moves,
+        }
+    }
+}
+
+/// plays the moves of a replay one after the other on its board
+pub struct ReplayPlayer<'a> {
+    moves: &'a [ReplayMove],
+    game: MineField,
+    next: usize,
+}
+
+impl<'a> ReplayPlayer<'a> {
+    /// the board once the moves played so far are
+    pub fn game(&self) -> &MineField {
+        &self.game
+    }
+
+    /// number of moves played so far
+    pub fn position(&self) -> usize {
+        self.next
+    }
+
+    /// play the next move, returning it, or `None` once they have all been played
+    pub fn step(&mut self) -> Result<Option<&'a ReplayMove>, GameError> {
+        let Some(replay_move) = self.moves.get(self.next) else {
+            return Ok(None);
+        };
+
+        self.game.try_play(replay_move.action.clone())?;
+        self.next += 1;
+        Ok(Some(replay_move))
+    }
+
+    /// take back the last move played, returning it
+    pub fn step_back(&mut self) -> Option<&'a ReplayMove> {
+        self.game.undo()?;
+        self.next -= 1;
+        self.moves.get(self.next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Grid, Strategy, StrategyChain};
+
+    #[test]
+    fn test_record_and_replay() {
+        let grid = Grid::new(9, 9)
+            .with_depth(2)
+            .with_topology(Topology::Torus)
+            .with_tiling(Tiling::Hexagonal)
+            .with_kernel(Kernel::cross());
+        let mut game = MineField::seeded_on(grid, 20, 5);
+        let mut recorder = Recorder::new();
+        let solver = StrategyChain::standard();
+
+        recorder
+            .play(&mut game, Action::Flag((0, 0).into()))
+            .unwrap();
+        recorder
+            .play(&mut game, Action::Unflag((0, 0).into()))
+            .unwrap();
+        game.undo();
+        while let Some(next_move) = solver.next_move(&game.view()) {
+            if recorder.play(&mut game, next_move).unwrap() != GameState::Ongoing {
+                break;
+            }
+        }
+
+        let replay: Replay = recorder.finish(&game).to_string().parse().unwrap();
+        // the first click hit a mine, which moved away from where the seed put it
+        assert_eq!(replay.seed, None);
+        assert_eq!(replay.board.grid(), game.grid());
+        assert_eq!(replay.moves.len(), game.history().len());
+        assert_eq!(replay.moves[0].strategy, None);
+        assert!(replay.moves[1].strategy.is_some());
+
+        let mut player = replay.player();
+        while player.step().unwrap().is_some() {}
+        assert_eq!(player.game().to_text(), game.to_text());
+        assert_eq!(player.game().state(), game.state());
+
+        assert_eq!(player.step_back(), replay.moves.last());
+        assert_eq!(player.position(), replay.moves.len() - 1);
+    }
+
+    #[test]
+    fn test_replay_win_condition() {
+        let mut game = "..*\n"
+            .parse::<MineField>()
+            .unwrap()
+            .with_win_condition(WinCondition::FlagAllMines);
+        let mut recorder = Recorder::new();
+        recorder
+            .play(&mut game, Action::Flag((2, 0).into()))
+            .unwrap();
+        assert_eq!(game.state(), GameState::Won);
+
+        let text = recorder.finish(&game).to_string();
+        assert!(text.contains("\nwin flag-all-mines\n"));
+
+        let replay: Replay = text.parse().unwrap();
+        let mut player = replay.player();
+        while player.step().unwrap().is_some() {}
+        assert_eq!(player.game().state(), GameState::Won);
+    }
+
+    #[test]
+    fn test_invalid_replay() {
+        assert!(
+            "minesweeper replay\nboard 2x1\n1*\nmoves\n0.000 - open (0, 0)\n"
+                .parse::<Replay>()
+                .is_ok()
+        );
+        assert!(
+            "minesweeper replay\nboard 2x1\n1*\nmoves\nopen (0, 0)\n"
+                .parse::<Replay>()
+                .is_err()
+        );
+        assert!(
+            "minesweeper replay\nboard 2x2\n1*\nmoves\n"
+                .parse::<Replay>()
+                .is_err()
+        );
+    }
+}
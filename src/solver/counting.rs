@@ -0,0 +1,199 @@
+use super::{
+    Frontier, Strategy,
+    invariant::{ClusterSolutions, describe_arrangements},
+};
+use crate::{Action, Move, PlayerView, Position};
+
+pub struct MineCountStrategy;
+
+impl Strategy for MineCountStrategy {
+    fn name(&self) -> &'static str {
+        "counting"
+    }
+
+    fn next_move(&self, game: &PlayerView) -> Option<Move> {
+        self.next_move_on(&Frontier::new(game))
+    }
+
+    fn next_move_on(&self, frontier: &Frontier) -> Option<Move> {
+        find_mine_count_move(frontier)
+    }
+}
+
+/// keep the solutions of a cluster leaving a number of mines the rest of the closed cells can hold
+fn solutions_within_count(
+    cluster: &ClusterSolutions,
+    remaining_mines: usize,
+    closed_cells: usize,
+) -> Vec<&Vec<bool>> {
+    let other_cells = closed_cells - cluster.cells.len();
+
+    cluster
+        .solutions
+        .iter()
+        .filter(|solution| {
+            let mines = solution.iter().filter(|mine| **mine).count();
+            mines <= remaining_mines && remaining_mines - mines <= other_cells
+        })
+        .collect()
+}
+
+/// a cell of `cluster` which is mined, or safe, in every one of the `solutions` fitting the count of `mines_left`
+pub(super) fn decide_cluster(
+    cluster: &ClusterSolutions,
+    solutions: &[&Vec<bool>],
+    mines_left: &str,
+) -> Option<Move> {
+    for (index, pos) in cluster.cells.iter().enumerate() {
+        if solutions.iter().all(|solution| solution[index]) {
+            return Some(Move::new(Action::Flag(*pos)).with_reason(format!(
+                "mined in every arrangement of its cluster fitting {mines_left} ({})",
+                describe_arrangements(&cluster.cells, solutions)
+            )));
+        }
+
+        if solutions.iter().all(|solution| !solution[index]) {
+            return Some(Move::new(Action::Open(*pos)).with_reason(format!(
+                "safe in every arrangement of its cluster fitting {mines_left} ({})",
+                describe_arrangements(&cluster.cells, solutions)
+            )));
+        }
+    }
+
+    None
+}
+
+/// the sea is safe when every possible number of mines `leftovers` it may hold is 0, and mined when every one of them
+/// fills it; nothing can be said of it when there is none, the board being inconsistent
+pub(super) fn decide_sea(sea: &[Position], leftovers: &[usize]) -> Option<Move> {
+    let pos = *sea.first()?;
+
+    if leftovers.is_empty() {
+        None
+    } else if leftovers.iter().all(|mines| *mines == 0) {
+        Some(
+            Move::new(Action::Open(pos)).with_reason("no mine is left out of reach of the numbers"),
+        )
+    } else if leftovers.iter().all(|mines| *mines == sea.len()) {
+        Some(
+            Move::new(Action::Flag(pos))
+                .with_reason("every cell out of reach of the numbers holds a mine"),
+        )
+    } else {
+        None
+    }
+}
+
+/// a cluster solution must not use more mines than are left on the board, nor leave more than the other closed
+/// cells can hold
+/// when the frontier is a single cluster, the cells out of reach of any number (the sea) hold exactly the mines
+/// the cluster leaves, so the sea is safe when no solution leaves any, and mined when all leave as many as it has cells
+pub(super) fn find_mine_count_move(frontier: &Frontier) -> Option<Move> {
+    let game = frontier.game();
+    let remaining_mines = usize::try_from(game.remaining_mines()).ok()?;
+    let clusters = frontier.clusters();
+
+    let closed_cells = game
+        .grid()
+        .positions()
+        .filter(|pos| game.cell_data(*pos).is_closed())
+        .count();
+    let mines_left = format!("the {remaining_mines} mines left");
+
+    let mut leftovers = Vec::new();
+
+    for cluster in clusters {
+        let solutions = solutions_within_count(cluster, remaining_mines, closed_cells);
+
+        if solutions.is_empty() {
+            continue;
+        }
+
+        if let Some(next_move) = decide_cluster(cluster, &solutions, &mines_left) {
+            return Some(next_move);
+        }
+
+        leftovers = solutions
+            .iter()
+            .map(|solution| remaining_mines - solution.iter().filter(|mine| **mine).count())
+            .collect();
+    }
+
+    if clusters.len() > 1 {
+        return None;
+    }
+
+    if clusters.is_empty() {
+        leftovers.push(remaining_mines);
+    }
+
+    decide_sea(&frontier.sea(), &leftovers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CellConfig, MineField};
+
+    #[test]
+    fn test_count_rules_out_cluster_solution() {
+        let game = MineField::from([[
+            CellConfig::Closed,
+            CellConfig::Open,
+            CellConfig::Mine,
+            CellConfig::Open,
+            CellConfig::Closed,
+        ]]);
+
+        let next_move = MineCountStrategy
+            .next_move(&game.view())
+            .map(|next_move| next_move.action);
+
+        match next_move {
+            None => panic!("Counting solver should find a move for 1 mine around 11"),
+            Some(Action::Open(pos)) if pos == (0, 0).into() => {}
+            Some(Action::Flag(pos)) if pos == (2, 0).into() => {}
+            Some(Action::Open(pos)) if pos == (4, 0).into() => {}
+            _ => panic!("Invalid move"),
+        }
+    }
+
+    #[test]
+    fn test_sea_without_mines_left() {
+        let game = MineField::from([[CellConfig::Flagged, CellConfig::Closed, CellConfig::Closed]]);
+
+        let next_move = MineCountStrategy
+            .next_move(&game.view())
+            .map(|next_move| next_move.action);
+
+        match next_move {
+            Some(Action::Open(pos)) if pos != (0, 0).into() => {}
+            _ => panic!("Invalid move"),
+        }
+    }
+
+    #[test]
+    fn test_sea_of_an_inconsistent_board() {
+        // the number needs a mine, but none is left
+        let view = PlayerView::from_text("1...\n", 0).unwrap();
+
+        assert_eq!(MineCountStrategy.next_move(&view), None);
+    }
+
+    #[test]
+    fn test_sea_full_of_mines() {
+        let game = MineField::from([[
+            CellConfig::Closed,
+            CellConfig::Open,
+            CellConfig::Mine,
+            CellConfig::Mine,
+            CellConfig::Mine,
+        ]]);
+
+        let next_move = MineCountStrategy
+            .next_move(&game.view())
+            .map(|next_move| next_move.action);
+
+        assert_eq!(next_move, Some(Action::Flag((3, 0).into())));
+    }
+}
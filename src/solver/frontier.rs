@@ -0,0 +1,59 @@
+use std::cell::OnceCell;
+
+use super::invariant::{ClusterSolutions, solve_all_clusters};
+use crate::{PlayerView, Position};
+
+/// a board along with what the stages of a chain work out on it, so that the clusters of its frontier are solved once
+/// for all the stages needing them rather than once by each of them
+pub struct Frontier<'a> {
+    game: &'a PlayerView,
+    clusters: OnceCell<Vec<ClusterSolutions>>,
+}
+
+impl<'a> Frontier<'a> {
+    pub fn new(game: &'a PlayerView) -> Self {
+        Self {
+            game,
+            clusters: OnceCell::new(),
+        }
+    }
+
+    pub fn game(&self) -> &'a PlayerView {
+        self.game
+    }
+
+    /// the solutions of every cluster along the frontier, see [`solve_all_clusters`]
+    pub(super) fn clusters(&self) -> &[ClusterSolutions] {
+        self.clusters.get_or_init(|| solve_all_clusters(self.game))
+    }
+
+    /// closed cells out of every cluster, the sea, as well as those of the clusters too large to solve
+    pub(super) fn sea(&self) -> Vec<Position> {
+        let grid = self.game.grid();
+        let mut clustered = vec![false; grid.len()];
+        for cell in self.clusters().iter().flat_map(|cluster| &cluster.cells) {
+            clustered[grid.index(*cell)] = true;
+        }
+
+        grid.positions()
+            .filter(|pos| self.game.cell_data(*pos).is_closed() && !clustered[grid.index(*pos)])
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sea_out_of_the_clusters() {
+        let view = PlayerView::from_text("1..\n...\n", 1).unwrap();
+        let frontier = Frontier::new(&view);
+
+        assert_eq!(frontier.clusters().len(), 1);
+        assert_eq!(
+            frontier.sea(),
+            [(2, 0).into(), (2, 1).into()] as [Position; 2]
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use std::fmt::Display;
+
+use super::{Strategy, StrategyChain};
+use crate::{Action, PlayerView};
+
+/// a move suggested to a human player, and why the solver suggests it
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hint {
+    pub action: Action,
+    /// name of the strategy which found the move
+    pub strategy: &'static str,
+    /// whether the move is certain to be right, rather than a guess
+    pub certain: bool,
+    pub justification: String,
+}
+
+impl Display for Hint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.certain {
+            f.write_str("maybe ")?;
+        }
+        write!(f, "{}: {}", self.action, self.justification)
+    }
+}
+
+impl StrategyChain {
+    /// the move the chain would play next on `game`, along with the reasoning of the strategy which found it
+    pub fn hint(&self, game: &PlayerView) -> Option<Hint> {
+        let next_move = self.next_move(game)?;
+        let strategy = next_move.strategy.unwrap_or(self.name());
+        let certain = self
+            .iter()
+            .find(|candidate| candidate.name() == strategy)
+            .is_some_and(|candidate| candidate.is_deterministic());
+
+        Some(Hint {
+            justification: next_move
+                .reason
+                .unwrap_or_else(|| format!("found by the {strategy} strategy")),
+            action: next_move.action,
+            strategy,
+            certain,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CellConfig, MineField, find_hint};
+
+    #[test]
+    fn test_hint_explains_immediate_move() {
+        let game = MineField::from([[CellConfig::Open, CellConfig::Mine, CellConfig::Open]]);
+
+        let hint = find_hint(&game.view()).unwrap();
+
+        assert_eq!(hint.action, Action::Flag((1, 0).into()));
+        assert_eq!(hint.strategy, "immediate");
+        assert!(hint.certain);
+        assert_eq!(
+            hint.justification,
+            "(0, 0) shows 1 and has exactly 1 closed neighbour"
+        );
+    }
+
+    #[test]
+    fn test_hint_lists_cluster_arrangements() {
+        let game = MineField::from([
+            [CellConfig::Mine, CellConfig::Closed, CellConfig::Closed],
+            [CellConfig::Open, CellConfig::Open, CellConfig::Mine],
+            [CellConfig::Open, CellConfig::Open, CellConfig::Open],
+        ]);
+
+        let hint = StrategyChain::new()
+            .with(crate::InvariantStrategy)
+            .hint(&game.view())
+            .unwrap();
+
+        assert_eq!(hint.action, Action::Open((2, 0).into()));
+        assert_eq!(
+            hint.justification,
+            "safe in every arrangement of its cluster (2 arrangements of 4 cells, mines on {(1, 0), (2, 1)} or \
+             {(0, 0), (2, 1)})"
+        );
+    }
+}
@@ -1,51 +1,86 @@
-use crate::{CellData, MineField, Position};
+use super::{Strategy, counted};
+use crate::{Action, CellData, Move, PlayerView, Position};
 
-/// when a cell's neighbouring mines are all flagged, open all remaining closed neighbours
+type NeighbourData = (Position, CellData);
+
+pub struct ImmediateStrategy;
+
+impl Strategy for ImmediateStrategy {
+    fn name(&self) -> &'static str {
+        "immediate"
+    }
+
+    fn next_move(&self, game: &PlayerView) -> Option<Move> {
+        find_immediate_move(game)
+    }
+}
+
+/// when a cell's neighbouring mines are all flagged, open all remaining closed neighbours, chording when there are several
 /// when a cell's closed neighbourhood size is equal to its mine own count, all these neighbours are bombs, flag them
-pub(super) fn find_immediate_move(game: &MineField) -> Option<(Position, bool)> {
-    for y in 0..game.height() {
-        for x in 0..game.height() {
-            let pos = (x, y).into();
-
-            match game.cell_data(pos) {
-                CellData::Flagged | CellData::Closed => {}
-                CellData::Open(mines_around) => {
-                    let neighbours = pos.neighbours(game);
-                    let mut neighbours: (Vec<(Position, CellData)>, Vec<(Position, CellData)>) =
-                        neighbours
-                            .into_iter()
-                            .map(|neighbour| (neighbour, game.cell_data(neighbour)))
-                            .filter(|(_, cell)| match cell {
-                                CellData::Closed | CellData::Flagged => true,
-                                CellData::Open(_) => false,
-                            })
-                            .partition(|(_, cell)| {
-                                if let CellData::Closed = cell {
-                                    true
-                                } else {
-                                    false
-                                }
-                            });
-
-                    let closed_positions = neighbours.0.len();
-                    let mines_flagged = neighbours.1.len();
-
-                    let mines_left = mines_around - mines_flagged;
-
-                    if !(mines_left == 0 || closed_positions == mines_left) {
-                        continue;
-                    }
+pub(super) fn find_immediate_move(game: &PlayerView) -> Option<Move> {
+    for pos in game.grid().positions() {
+        match game.cell_data(pos) {
+            CellData::Flagged | CellData::Closed | CellData::Questioned => {}
+            CellData::Open(mines_around) => {
+                let neighbours = pos.neighbours(game);
+                let mut neighbours: (Vec<NeighbourData>, Vec<NeighbourData>) = neighbours
+                    .into_iter()
+                    .map(|neighbour| (neighbour, game.cell_data(neighbour)))
+                    .filter(|(_, cell)| match cell {
+                        CellData::Closed | CellData::Questioned | CellData::Flagged => true,
+                        CellData::Open(_) => false,
+                    })
+                    .partition(|(_, cell)| cell.is_closed());
+
+                let closed_positions = neighbours.0.len();
+                let mines_flagged = neighbours.1.len();
 
+                // more flags than mines around, the player misplaced some of them
+                let Some(mines_left) = mines_around.checked_sub(mines_flagged) else {
+                    continue;
+                };
+
+                if !(mines_left == 0 || closed_positions == mines_left) {
+                    continue;
+                }
+
+                if mines_left > 0 {
                     let Some((neighbour, _)) = neighbours.0.pop() else {
                         continue;
                     };
 
-                    if mines_left > 0 {
-                        println!("Flagging {neighbour}");
+                    log::debug!(strategy = "immediate"; "Flagging {neighbour}");
+                    let reason = if mines_flagged == 0 {
+                        format!(
+                            "{pos} shows {mines_around} and has exactly {}",
+                            counted(closed_positions, "closed neighbour")
+                        )
                     } else {
-                        println!("Opening {neighbour}");
+                        format!(
+                            "{pos} shows {mines_around}, has {} and exactly {}",
+                            counted(mines_flagged, "flagged neighbour"),
+                            counted(closed_positions, "closed one")
+                        )
+                    };
+                    return Some(Move::new(Action::Flag(neighbour)).with_reason(reason));
+                }
+
+                match neighbours.0.as_slice() {
+                    [] => continue,
+                    [(neighbour, _)] => {
+                        log::debug!(strategy = "immediate"; "Opening {neighbour}");
+                        return Some(Move::new(Action::Open(*neighbour)).with_reason(format!(
+                            "{pos} shows {mines_around} and already has {}",
+                            counted(mines_flagged, "flagged neighbour")
+                        )));
+                    }
+                    _ => {
+                        log::debug!(strategy = "immediate"; "Chording {pos}");
+                        return Some(Move::new(Action::Chord(pos)).with_reason(format!(
+                            "{pos} shows {mines_around} and already has {}",
+                            counted(mines_flagged, "flagged neighbour")
+                        )));
                     }
-                    return Some((neighbour, mines_left > 0));
                 }
             }
         }
@@ -57,15 +92,15 @@ pub(super) fn find_immediate_move(game: &MineField) -> Option<(Position, bool)>
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{CellConfig, MineField};
+    use crate::{CellConfig, MineField, Topology};
 
     #[test]
     fn test_immediate_move_1bo() {
         let game = MineField::from([[CellConfig::Open, CellConfig::Mine, CellConfig::Open]]);
 
-        let next_move = find_immediate_move(&game);
+        let next_move = find_immediate_move(&game.view()).map(|next_move| next_move.action);
 
-        assert_eq!(next_move, Some((Position::from(1, 0), true)));
+        assert_eq!(next_move, Some(Action::Flag(Position::from(1, 0))));
     }
 
     #[test]
@@ -75,8 +110,50 @@ mod tests {
             [CellConfig::Open, CellConfig::Flagged, CellConfig::Closed],
         ]);
 
-        let next_move = find_immediate_move(&game);
+        let next_move = find_immediate_move(&game.view()).map(|next_move| next_move.action);
+
+        assert_eq!(next_move, Some(Action::Flag(Position::from(1, 0))));
+    }
+
+    #[test]
+    fn test_immediate_move_reason_counts_neighbours() {
+        let game = MineField::from([
+            [CellConfig::Open, CellConfig::Mine, CellConfig::Closed],
+            [CellConfig::Mine, CellConfig::Flagged, CellConfig::Closed],
+        ]);
+
+        let next_move = find_immediate_move(&game.view()).unwrap();
+
+        assert_eq!(
+            next_move.reason.as_deref(),
+            Some("(0, 0) shows 3, has 1 flagged neighbour and exactly 2 closed ones")
+        );
+    }
+
+    #[test]
+    fn test_immediate_move_chord() {
+        let game = MineField::from([
+            [CellConfig::Flagged, CellConfig::Open, CellConfig::Closed],
+            [CellConfig::Closed, CellConfig::Closed, CellConfig::Closed],
+        ]);
+
+        let next_move = find_immediate_move(&game.view()).map(|next_move| next_move.action);
+
+        assert_eq!(next_move, Some(Action::Chord(Position::from(1, 0))));
+    }
+
+    #[test]
+    fn test_immediate_move_across_joined_edge() {
+        let game = MineField::from([[
+            CellConfig::Open,
+            CellConfig::Open,
+            CellConfig::Closed,
+            CellConfig::Mine,
+        ]])
+        .with_topology(Topology::WrapHorizontally);
+
+        let next_move = find_immediate_move(&game.view()).map(|next_move| next_move.action);
 
-        assert_eq!(next_move, Some((Position::from(1, 0), true)));
+        assert_eq!(next_move, Some(Action::Flag(Position::from(3, 0))));
     }
 }
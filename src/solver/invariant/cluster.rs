@@ -24,8 +24,34 @@ impl<'a, 'b> Cluster<'a, 'b> {
     pub fn from(shadow_minefield: &'a mut ShadowMinefield<'b>, initial_pos: Position) -> Self {
         let mut clusterer = Clusterer::from(shadow_minefield);
 
-        let closed_positions: Vec<ClusterCell> = clusterer
-            .find_closed_positions(initial_pos)
+        let found = clusterer.find_closed_positions(initial_pos);
+        let game = clusterer.shadow_minefield.game;
+        let mut numbers: Vec<Position> = found
+            .iter()
+            .flat_map(|pos| clusterer.get_open_neighbours(*pos))
+            .collect();
+        numbers.sort_by_key(|pos| game.grid().index(*pos));
+        numbers.dedup();
+
+        // the cells around a number are decided one after the other, number by number, so that arrangements breaking
+        // one are dropped early rather than after deciding most of the cluster; cells are decided from the end
+        let mut unplaced = vec![false; game.grid().len()];
+        for pos in &found {
+            unplaced[game.grid().index(*pos)] = true;
+        }
+        let mut positions = Vec::with_capacity(found.len());
+        for number in numbers {
+            for neighbour in number.neighbours(game) {
+                let index = game.grid().index(neighbour);
+                if unplaced[index] {
+                    unplaced[index] = false;
+                    positions.push(neighbour);
+                }
+            }
+        }
+        positions.reverse();
+
+        let closed_positions: Vec<ClusterCell> = positions
             .into_iter()
             .map(|pos| ClusterCell {
                 pos,
@@ -34,8 +60,8 @@ impl<'a, 'b> Cluster<'a, 'b> {
             .collect();
 
         let open_positions: Vec<Position> = closed_positions
-            .to_vec()
-            .into_iter()
+            .iter()
+            .cloned()
             .flat_map(|cell| cell.open_neighbours)
             .collect();
 
@@ -51,28 +77,22 @@ impl<'a, 'b> std::fmt::Display for Cluster<'a, 'b> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str(&"—".repeat(self.shadow_minefield.game.width()))?;
 
-        for (y, row) in self
-            .shadow_minefield
-            .field
-            .chunks(self.shadow_minefield.game.width())
-            .enumerate()
+        let game = self.shadow_minefield.game;
+        for (pos, cell) in game
+            .grid()
+            .all_positions()
+            .zip(&self.shadow_minefield.field)
         {
-            f.write_str("\n")?;
-            for (x, cell) in row.iter().enumerate() {
-                let pos = (x, y).into();
-
-                if self
-                    .closed_positions
-                    .iter()
-                    .find(|c| c.pos == pos)
-                    .is_some()
-                {
-                    cell.fmt(f)?
-                } else if self.open_positions.contains(&pos) {
-                    cell.fmt(f)?
-                } else {
-                    f.write_str(" ")?
-                }
+            if pos.x() == 0 {
+                f.write_str("\n")?;
+            }
+
+            if self.closed_positions.iter().any(|c| c.pos == pos)
+                || self.open_positions.contains(&pos)
+            {
+                cell.fmt(f)?
+            } else {
+                f.write_str(" ")?
             }
         }
 
@@ -87,7 +107,7 @@ struct Clusterer<'a, 'b> {
 
 impl<'a, 'b> Clusterer<'a, 'b> {
     fn from(shadow_minefield: &'a ShadowMinefield<'b>) -> Self {
-        let visited = vec![false; shadow_minefield.game.width() * shadow_minefield.game.height()];
+        let visited = vec![false; shadow_minefield.game.grid().len()];
         Self {
             shadow_minefield,
             visited,
@@ -101,26 +121,37 @@ impl<'a, 'b> Clusterer<'a, 'b> {
             .collect()
     }
 
-    fn find_closed_positions(&mut self, pos: Position) -> Vec<Position> {
-        self.visited[pos.to_index(self.shadow_minefield.game.width())] = true;
+    /// closed cells reached from `initial_pos` going from closed cells to open ones and back, depth first with a stack
+    /// of its own rather than recursion, which large clusters would overflow
+    fn find_closed_positions(&mut self, initial_pos: Position) -> Vec<Position> {
+        let game = self.shadow_minefield.game;
+        let mut cluster = Vec::new();
+        let mut stack = vec![initial_pos];
 
-        let is_closed = self.shadow_minefield.get_cell(&pos).is_closed();
-        let is_open = self.shadow_minefield.get_cell(&pos).is_open();
+        while let Some(pos) = stack.pop() {
+            if self.visited[game.grid().index(pos)] {
+                continue;
+            }
+            self.visited[game.grid().index(pos)] = true;
 
-        let mut cluster = Vec::new();
-        if is_closed {
-            cluster.push(pos)
-        } else if is_open {
-        } else {
-            unreachable!()
-        }
+            let is_closed = self.shadow_minefield.get_cell(&pos).is_closed();
+            let is_open = self.shadow_minefield.get_cell(&pos).is_open();
 
-        for neighbour in pos.neighbours(self.shadow_minefield.game) {
-            if !self.visited[neighbour.to_index(self.shadow_minefield.game.width())]
-                && ((self.shadow_minefield.get_cell(&neighbour).is_closed() && is_open)
-                    || (self.shadow_minefield.get_cell(&neighbour).is_open() && is_closed))
-            {
-                cluster.extend(self.find_closed_positions(neighbour))
+            if is_closed {
+                cluster.push(pos)
+            } else if is_open {
+            } else {
+                unreachable!()
+            }
+
+            // in reverse, so that neighbours are searched in the order of the grid
+            for neighbour in pos.neighbours(game).into_iter().rev() {
+                if !self.visited[game.grid().index(neighbour)]
+                    && ((self.shadow_minefield.get_cell(&neighbour).is_closed() && is_open)
+                        || (self.shadow_minefield.get_cell(&neighbour).is_open() && is_closed))
+                {
+                    stack.push(neighbour);
+                }
             }
         }
 
@@ -1,19 +1,45 @@
 mod cluster;
 mod shadow;
 
-use crate::{CellData, MineField, Position, solver::invariant::cluster::Cluster};
+use super::{Strategy, counted};
+use crate::{Action, CellData, Move, PlayerView, Position, solver::invariant::cluster::Cluster};
 use cluster::ClusterCell;
 use shadow::ShadowMinefield;
 
+pub struct InvariantStrategy;
+
+impl Strategy for InvariantStrategy {
+    fn name(&self) -> &'static str {
+        "invariant"
+    }
+
+    fn next_move(&self, game: &PlayerView) -> Option<Move> {
+        find_invariant_move(game)
+    }
+}
+
+/// steps a search may still take, and whether it needed more than it was given
+struct Budget {
+    steps_left: usize,
+    exhausted: bool,
+}
+
 fn generate_suitable_cluster_solutions(
     shadow_minefield: &mut ShadowMinefield,
     open_positions: &Vec<Position>,
     cluster: &mut Vec<ClusterCell>,
+    budget: &mut Budget,
 ) -> Vec<Vec<bool>> {
+    if budget.steps_left == 0 {
+        budget.exhausted = true;
+        return vec![];
+    }
+    budget.steps_left -= 1;
+
     let Some(cell) = cluster.pop() else {
         // println!("{shadow_minefield}");
         if open_positions
-            .into_iter()
+            .iter()
             .all(|pos| shadow_minefield.get_cell(pos).get_mines_left() == Some(0))
         {
             // println!("Valid solution!");
@@ -28,7 +54,7 @@ fn generate_suitable_cluster_solutions(
 
     if shadow_minefield.flag(&cell) {
         let mut sub_solutions =
-            generate_suitable_cluster_solutions(shadow_minefield, open_positions, cluster);
+            generate_suitable_cluster_solutions(shadow_minefield, open_positions, cluster, budget);
 
         for sub_solution in sub_solutions.iter_mut() {
             sub_solution.push(true);
@@ -41,7 +67,7 @@ fn generate_suitable_cluster_solutions(
 
     if shadow_minefield.open(&cell) {
         let mut sub_solutions =
-            generate_suitable_cluster_solutions(shadow_minefield, open_positions, cluster);
+            generate_suitable_cluster_solutions(shadow_minefield, open_positions, cluster, budget);
 
         for sub_solution in sub_solutions.iter_mut() {
             sub_solution.push(false);
@@ -57,31 +83,109 @@ fn generate_suitable_cluster_solutions(
     solutions
 }
 
-fn find_invariant_solution(
-    cluster: Vec<ClusterCell>,
-    solutions: Vec<Vec<bool>>,
-) -> Option<(Position, bool)> {
+/// steps of the search for the arrangements of a cluster, past which it is left undecided rather than searched for
+/// minutes, as clusters along numbers with many neighbours may allow millions of arrangements
+const MAX_SEARCH_STEPS: usize = 1_000_000;
+
+/// every arrangement of mines among the closed cells of `cluster` satisfying its numbers, `None` when they take more
+/// than `max_steps` to find
+fn solve_cluster(cluster: &mut Cluster, max_steps: usize) -> Option<Vec<Vec<bool>>> {
+    let mut budget = Budget {
+        steps_left: max_steps,
+        exhausted: false,
+    };
+    let solutions = generate_suitable_cluster_solutions(
+        cluster.shadow_minefield,
+        &cluster.open_positions,
+        &mut cluster.closed_positions,
+        &mut budget,
+    );
+    if budget.exhausted {
+        log::debug!(cluster_size = cluster.closed_positions.len(); "Too many arrangements to enumerate");
+        return None;
+    }
+
+    // the cells were searched number by number, but are handed out in the order of the grid, and so are the
+    // arrangements, as a search in that order finds them
+    let grid = cluster.shadow_minefield.game.grid();
+    let mut order: Vec<usize> = (0..cluster.closed_positions.len()).collect();
+    order.sort_by_key(|index| grid.index(cluster.closed_positions[*index].pos));
+    cluster.closed_positions = order
+        .iter()
+        .map(|index| cluster.closed_positions[*index].clone())
+        .collect();
+    let mut solutions: Vec<Vec<bool>> = solutions
+        .into_iter()
+        .map(|solution| order.iter().map(|index| solution[*index]).collect())
+        .collect();
+    solutions.sort_by(|a, b| b.iter().rev().cmp(a.iter().rev()));
+
+    Some(solutions)
+}
+
+/// the number of arrangements of mines a cluster allows, and the mined cells of the first few of them
+pub(super) fn describe_arrangements(
+    cells: &[Position],
+    solutions: &[impl AsRef<[bool]>],
+) -> String {
+    const SHOWN: usize = 3;
+
+    let mut arrangements: Vec<String> = solutions
+        .iter()
+        .take(SHOWN)
+        .map(|solution| {
+            let mines: Vec<String> = cells
+                .iter()
+                .zip(solution.as_ref())
+                .filter(|(_, mine)| **mine)
+                .map(|(pos, _)| pos.to_string())
+                .collect();
+            format!("{{{}}}", mines.join(", "))
+        })
+        .collect();
+    if solutions.len() > SHOWN {
+        arrangements.push("...".to_string());
+    }
+
+    format!(
+        "{} of {}, mines on {}",
+        counted(solutions.len(), "arrangement"),
+        counted(cells.len(), "cell"),
+        arrangements.join(" or ")
+    )
+}
+
+fn find_invariant_solution(cluster: Vec<ClusterCell>, solutions: Vec<Vec<bool>>) -> Option<Move> {
     // let neighbours = pos.neighbours(game);
+    let cells: Vec<Position> = cluster.iter().map(|cell| cell.pos).collect();
 
     for (index, cell) in cluster.iter().enumerate() {
         if solutions.iter().all(|solution| solution[index]) {
             // println!("Deciding to play ({}, true)", cell.pos);
-            return Some((cell.pos, true));
+            return Some(Move::new(Action::Flag(cell.pos)).with_reason(format!(
+                "mined in every arrangement of its cluster ({})",
+                describe_arrangements(&cells, &solutions)
+            )));
         }
 
         if solutions.iter().all(|solution| !solution[index]) {
             // println!("Deciding to play ({}, false)", cell.pos);
-            return Some((cell.pos, false));
+            return Some(Move::new(Action::Open(cell.pos)).with_reason(format!(
+                "safe in every arrangement of its cluster ({})",
+                describe_arrangements(&cells, &solutions)
+            )));
         }
     }
 
     None
 }
 
+/// `tried` marks the cells of the clusters already searched, which every other number of the cluster leads to again
 fn build_cluster_and_find_move(
     shadow_minefield: &mut ShadowMinefield,
     initial_pos: Position,
-) -> Option<(Position, bool)> {
+    tried: &mut [bool],
+) -> Option<Move> {
     // println!("Initial");
     // println!("{shadow_minefield}");
     let mut cluster = Cluster::from(shadow_minefield, initial_pos);
@@ -89,33 +193,95 @@ fn build_cluster_and_find_move(
     if cluster.closed_positions.is_empty() {
         return None;
     }
+    let grid = cluster.shadow_minefield.game.grid();
+    for cell in &cluster.closed_positions {
+        tried[grid.index(cell.pos)] = true;
+    }
 
-    let valid_solutions = generate_suitable_cluster_solutions(
-        cluster.shadow_minefield,
-        &cluster.open_positions,
-        &mut cluster.closed_positions,
+    let valid_solutions = solve_cluster(&mut cluster, MAX_SEARCH_STEPS)?;
+    log::trace!(
+        strategy = "invariant",
+        cluster_size = cluster.closed_positions.len(),
+        solutions = valid_solutions.len();
+        "Cluster: {cluster}"
     );
-    println!("Cluster: {}", cluster);
-    println!("Valid solutions: {valid_solutions:?}");
+    log::trace!(strategy = "invariant"; "Valid solutions: {valid_solutions:?}");
 
     find_invariant_solution(cluster.closed_positions, valid_solutions)
 }
 
-pub(super) fn find_invariant_move(game: &MineField) -> Option<(Position, bool)> {
+/// closed cells of one cluster, and every placement of mines among them satisfying their open neighbours
+pub(super) struct ClusterSolutions {
+    pub(super) cells: Vec<Position>,
+    pub(super) solutions: Vec<Vec<bool>>,
+}
+
+/// enumerate the solutions of every cluster along the frontier, each closed cell belonging to at most one of them
+///
+/// a cluster allowing too many solutions to enumerate, see [`MAX_SEARCH_STEPS`], is left out, its cells being taken
+/// for cells out of reach of the numbers: the arrangements of the other clusters are then a superset of the possible
+/// ones, which keeps what holds in all of them certain
+pub(super) fn solve_all_clusters(game: &PlayerView) -> Vec<ClusterSolutions> {
+    solve_clusters_within(game, MAX_SEARCH_STEPS)
+}
+
+fn solve_clusters_within(game: &PlayerView, max_steps: usize) -> Vec<ClusterSolutions> {
+    let mut shadow_minefield = ShadowMinefield::new(game);
+    let mut clustered = vec![false; game.grid().len()];
+    let mut clusters = Vec::new();
+
+    for pos in game.grid().positions() {
+        if !shadow_minefield.get_cell(&pos).is_open() {
+            continue;
+        }
+
+        let closed_positions = shadow_minefield.get_closed_positions(&pos);
+        if closed_positions.is_empty()
+            || closed_positions
+                .iter()
+                .any(|closed| clustered[game.grid().index(*closed)])
+        {
+            continue;
+        }
+
+        let mut cluster = Cluster::from(&mut shadow_minefield, pos);
+        let solutions = solve_cluster(&mut cluster, max_steps);
+
+        let cells: Vec<Position> = cluster
+            .closed_positions
+            .iter()
+            .map(|cell| cell.pos)
+            .collect();
+        for cell in &cells {
+            clustered[game.grid().index(*cell)] = true;
+        }
+
+        if let Some(solutions) = solutions {
+            clusters.push(ClusterSolutions { cells, solutions });
+        }
+    }
+
+    clusters
+}
+
+pub(super) fn find_invariant_move(game: &PlayerView) -> Option<Move> {
     let mut shadow_minefield = ShadowMinefield::new(game);
+    let mut tried = vec![false; game.grid().len()];
 
-    for y in 0..game.height() {
-        for x in 0..game.height() {
-            let pos = (x, y).into();
-
-            match game.cell_data(pos) {
-                CellData::Flagged | CellData::Closed | CellData::Open(0) => {}
-                CellData::Open(_) => {
-                    if let Some(next_move) = build_cluster_and_find_move(&mut shadow_minefield, pos)
-                    {
-                        println!("[invariant] Playing {next_move:?}");
-                        return Some(next_move);
-                    }
+    for pos in game.grid().positions() {
+        match game.cell_data(pos) {
+            CellData::Flagged | CellData::Closed | CellData::Questioned | CellData::Open(0) => {}
+            CellData::Open(_)
+                if pos
+                    .neighbours(game)
+                    .iter()
+                    .any(|neighbour| tried[game.grid().index(*neighbour)]) => {}
+            CellData::Open(_) => {
+                if let Some(next_move) =
+                    build_cluster_and_find_move(&mut shadow_minefield, pos, &mut tried)
+                {
+                    log::debug!(strategy = "invariant"; "[invariant] Playing {}", next_move.action);
+                    return Some(next_move);
                 }
             }
         }
@@ -3,12 +3,12 @@ use std::fmt::Display;
 use color_eyre::owo_colors::OwoColorize;
 
 use super::cluster::ClusterCell;
-use crate::{CellData, MineField, Position};
+use crate::{CellData, PlayerView, Position};
 
 #[derive(Debug)]
 pub(super) enum ShadowCell {
     Closed,
-    Open { mines: usize, mines_left: usize },
+    Open { mines_left: usize },
     ShadowOpen,
     Flagged,
     ShadowFlagged,
@@ -20,27 +20,18 @@ impl ShadowCell {
     }
 
     pub fn is_shadow_open(&self) -> bool {
-        match self {
-            ShadowCell::ShadowOpen => true,
-            _ => false,
-        }
+        matches!(self, ShadowCell::ShadowOpen)
     }
 
     pub fn get_mines_left(&self) -> Option<usize> {
         match self {
-            ShadowCell::Open {
-                mines: _,
-                mines_left,
-            } => Some(*mines_left),
+            ShadowCell::Open { mines_left } => Some(*mines_left),
             _ => None,
         }
     }
 
     pub fn is_closed(&self) -> bool {
-        match self {
-            ShadowCell::Closed => true,
-            _ => false,
-        }
+        matches!(self, ShadowCell::Closed)
     }
 
     // pub fn is_flagged(&self) -> bool {
@@ -51,10 +42,7 @@ impl ShadowCell {
     // }
 
     pub fn is_shadow_flagged(&self) -> bool {
-        match self {
-            ShadowCell::ShadowFlagged => true,
-            _ => false,
-        }
+        matches!(self, ShadowCell::ShadowFlagged)
     }
 }
 
@@ -64,10 +52,7 @@ impl Display for ShadowCell {
             ShadowCell::Closed => f.write_str("o"),
             ShadowCell::Flagged => f.write_str(&"F".black().to_string()),
             ShadowCell::ShadowFlagged => f.write_str("F"),
-            ShadowCell::Open {
-                mines: _,
-                mines_left,
-            } => {
+            ShadowCell::Open { mines_left } => {
                 let string = match mines_left {
                     0 => " ".black().into_styled(),
                     1 => "1".bright_blue().into_styled(),
@@ -78,7 +63,8 @@ impl Display for ShadowCell {
                     6 => "6".cyan().into_styled(),
                     7 => "7".black().into_styled(),
                     8 => "8".bright_black().into_styled(),
-                    _ => unreachable!("Invalid value of mines left"),
+                    // only boards of several layers have that many neighbours
+                    _ => return f.write_str(&mines_left.to_string().magenta().to_string()),
                 };
                 f.write_str(&string.to_string())
             }
@@ -88,30 +74,26 @@ impl Display for ShadowCell {
 }
 
 pub(super) struct ShadowMinefield<'a> {
-    pub(super) game: &'a MineField,
+    pub(super) game: &'a PlayerView,
     pub(super) field: Vec<ShadowCell>,
 }
 
 impl<'a> ShadowMinefield<'a> {
-    pub fn new(game: &'a MineField) -> Self {
+    pub fn new(game: &'a PlayerView) -> Self {
         let mut field = Vec::new();
-        for y in 0..game.height() {
-            for x in 0..game.width() {
-                let pos = (x, y).into();
-                field.push(match game.cell_data(pos) {
-                    CellData::Closed => ShadowCell::Closed,
-                    CellData::Open(mines) => ShadowCell::Open {
-                        mines,
-                        mines_left: mines - Self::mines_flagged(pos, game),
-                    },
-                    CellData::Flagged => ShadowCell::Flagged,
-                });
-            }
+        for pos in game.grid().all_positions() {
+            field.push(match game.cell_data(pos) {
+                CellData::Closed | CellData::Questioned => ShadowCell::Closed,
+                CellData::Open(mines) => ShadowCell::Open {
+                    mines_left: mines.saturating_sub(Self::mines_flagged(pos, game)),
+                },
+                CellData::Flagged => ShadowCell::Flagged,
+            });
         }
         Self { game, field }
     }
 
-    fn mines_flagged(pos: Position, game: &MineField) -> usize {
+    fn mines_flagged(pos: Position, game: &PlayerView) -> usize {
         pos.neighbours(game)
             .into_iter()
             .filter_map(|neighbour| match game.cell_data(neighbour) {
@@ -122,11 +104,11 @@ impl<'a> ShadowMinefield<'a> {
     }
 
     pub fn get_cell(&self, pos: &Position) -> &ShadowCell {
-        &self.field[pos.to_index(self.game.width())]
+        &self.field[self.game.grid().index(*pos)]
     }
 
     fn get_cell_mut(&mut self, pos: &Position) -> Option<&mut ShadowCell> {
-        self.field.get_mut(pos.to_index(self.game.width()))
+        self.field.get_mut(self.game.grid().index(*pos))
     }
 
     // pub fn get_remaining_mines(&self, pos: Position) -> usize {
@@ -151,7 +133,7 @@ impl<'a> ShadowMinefield<'a> {
 
     pub fn flag(&mut self, cell: &ClusterCell) -> bool {
         if !self.get_cell(&cell.pos).is_closed() {
-            panic!("Position cannot be shadow flagged")
+            return false;
         }
 
         for neighbour in &cell.open_neighbours {
@@ -161,11 +143,7 @@ impl<'a> ShadowMinefield<'a> {
         }
 
         for neighbour in &cell.open_neighbours {
-            if let Some(ShadowCell::Open {
-                mines: _,
-                mines_left,
-            }) = self.get_cell_mut(neighbour)
-            {
+            if let Some(ShadowCell::Open { mines_left }) = self.get_cell_mut(neighbour) {
                 *mines_left -= 1;
             }
         }
@@ -178,15 +156,11 @@ impl<'a> ShadowMinefield<'a> {
 
     pub fn unflag(&mut self, cell: &ClusterCell) {
         if !self.get_cell(&cell.pos).is_shadow_flagged() {
-            panic!("Position cannot be shadow unflagged")
+            return;
         }
 
         for neighbour in &cell.open_neighbours {
-            if let Some(ShadowCell::Open {
-                mines: _,
-                mines_left,
-            }) = self.get_cell_mut(neighbour)
-            {
+            if let Some(ShadowCell::Open { mines_left }) = self.get_cell_mut(neighbour) {
                 *mines_left += 1;
             }
         }
@@ -197,16 +171,15 @@ impl<'a> ShadowMinefield<'a> {
 
     pub fn open(&mut self, cell: &ClusterCell) -> bool {
         if !self.get_cell(&cell.pos).is_closed() {
-            panic!("Position cannot be shadow opened")
+            return false;
         }
 
+        // the cell is still closed, so a number whose closed neighbours are only as many as its mines left would lack
+        // room for them once it opens
         for neighbour in &cell.open_neighbours {
-            if let ShadowCell::Open {
-                mines: _,
-                mines_left,
-            } = self.get_cell(neighbour)
+            if let ShadowCell::Open { mines_left } = self.get_cell(neighbour)
                 && *mines_left > 0
-                && self.get_closed_positions(neighbour).len() < *mines_left
+                && self.get_closed_positions(neighbour).len() <= *mines_left
             {
                 return false;
             }
@@ -220,7 +193,7 @@ impl<'a> ShadowMinefield<'a> {
 
     pub fn unopen(&mut self, cell: &ClusterCell) {
         if !self.get_cell(&cell.pos).is_shadow_open() {
-            panic!("Position cannot be shadow opened")
+            return;
         }
 
         let cell = self.get_cell_mut(&cell.pos).unwrap();
@@ -1,4 +1,12 @@
-use crate::{CellConfig, MineField, solver::invariant::find_invariant_move};
+use crate::{
+    Action, CellConfig, Kernel, MineField, PlayerView, Position, Tiling,
+    solver::invariant::{
+        cluster::{Cluster, ClusterCell},
+        describe_arrangements, find_invariant_move,
+        shadow::ShadowMinefield,
+        solve_all_clusters, solve_clusters_within,
+    },
+};
 
 #[test]
 fn solve_121() {
@@ -7,13 +15,13 @@ fn solve_121() {
         [CellConfig::Mine, CellConfig::Closed, CellConfig::Mine],
     ]);
 
-    let next_move = find_invariant_move(&game);
+    let next_move = find_invariant_move(&game.view()).map(|next_move| next_move.action);
 
     match next_move {
         None => panic!("Invariant solver should find a move for 121"),
-        Some((pos, true)) if pos == (0, 1).into() => {}
-        Some((pos, false)) if pos == (1, 1).into() => {}
-        Some((pos, true)) if pos == (2, 1).into() => {}
+        Some(Action::Flag(pos)) if pos == (0, 1).into() => {}
+        Some(Action::Open(pos)) if pos == (1, 1).into() => {}
+        Some(Action::Flag(pos)) if pos == (2, 1).into() => {}
         _ => panic!("Invalid move"),
     }
 }
@@ -25,13 +33,63 @@ fn solve_111() {
         [CellConfig::Closed, CellConfig::Mine, CellConfig::Closed],
     ]);
 
-    let next_move = find_invariant_move(&game);
+    let next_move = find_invariant_move(&game.view()).map(|next_move| next_move.action);
 
     match next_move {
         None => panic!("Invariant solver should find a move for 111"),
-        Some((pos, false)) if pos == (0, 1).into() => {}
-        Some((pos, true)) if pos == (1, 1).into() => {}
-        Some((pos, false)) if pos == (2, 1).into() => {}
+        Some(Action::Open(pos)) if pos == (0, 1).into() => {}
+        Some(Action::Flag(pos)) if pos == (1, 1).into() => {}
+        Some(Action::Open(pos)) if pos == (2, 1).into() => {}
+        _ => panic!("Invalid move"),
+    }
+}
+
+#[test]
+fn solve_hexagonal() {
+    // the open cells show 0, 1 and 1, only touching the cell below them and the one below to the left
+    let game = MineField::from([
+        [CellConfig::Open, CellConfig::Open, CellConfig::Open],
+        [CellConfig::Closed, CellConfig::Mine, CellConfig::Closed],
+    ])
+    .with_tiling(Tiling::Hexagonal);
+
+    let next_move = find_invariant_move(&game.view()).map(|next_move| next_move.action);
+
+    match next_move {
+        None => panic!("Invariant solver should find a move on a hexagonal board"),
+        Some(Action::Open(pos)) if pos == (0, 1).into() => {}
+        Some(Action::Flag(pos)) if pos == (1, 1).into() => {}
+        Some(Action::Open(pos)) if pos == (2, 1).into() => {}
+        _ => panic!("Invalid move"),
+    }
+}
+
+#[test]
+fn solve_across_layers() {
+    // the only closed cell lies on the second layer, above the numbers of the first one
+    let game: MineField = "11\n--\n1*\n".parse().unwrap();
+
+    let next_move = find_invariant_move(&game.view()).map(|next_move| next_move.action);
+
+    assert_eq!(next_move, Some(Action::Flag((1, 0, 1).into())));
+}
+
+#[test]
+fn solve_knight() {
+    // the open cells count the mines a knight's move away, which (2, 1) is from (0, 2) and (1, 2) from (2, 0)
+    let game = MineField::from([
+        [CellConfig::Open, CellConfig::Open, CellConfig::Open],
+        [CellConfig::Open, CellConfig::Open, CellConfig::Mine],
+        [CellConfig::Open, CellConfig::Closed, CellConfig::Open],
+    ])
+    .with_kernel(Kernel::knight());
+
+    let next_move = find_invariant_move(&game.view()).map(|next_move| next_move.action);
+
+    match next_move {
+        None => panic!("Invariant solver should find a move with a knight's kernel"),
+        Some(Action::Flag(pos)) if pos == (2, 1).into() => {}
+        Some(Action::Open(pos)) if pos == (1, 2).into() => {}
         _ => panic!("Invalid move"),
     }
 }
@@ -44,11 +102,110 @@ fn solve_121_angle() {
         [CellConfig::Open, CellConfig::Open, CellConfig::Closed],
     ]);
 
-    let next_move = find_invariant_move(&game);
+    let next_move = find_invariant_move(&game.view()).map(|next_move| next_move.action);
 
     match next_move {
         None => panic!("Invariant solver should find a move for 121 angled"),
-        Some((pos, false)) if pos == (2, 0).into() => {}
+        Some(Action::Open(pos)) if pos == (2, 0).into() => {}
         _ => panic!("Invalid move"),
     }
 }
+
+#[test]
+fn solve_121_column_fixture() {
+    let game: MineField = include_str!("../../../fixtures/121-column.txt")
+        .parse()
+        .unwrap();
+
+    let next_move = find_invariant_move(&game.view()).map(|next_move| next_move.action);
+
+    match next_move {
+        None => panic!("Invariant solver should find a move for 121 in a column"),
+        Some(Action::Flag(pos)) if pos == (2, 0).into() => {}
+        Some(Action::Open(pos)) if pos == (2, 1).into() => {}
+        Some(Action::Flag(pos)) if pos == (2, 2).into() => {}
+        _ => panic!("Invalid move"),
+    }
+}
+
+#[test]
+fn clusters_in_the_order_of_the_grid() {
+    let view = PlayerView::from_text("1.\n..\n", 1).unwrap();
+    let clusters = solve_all_clusters(&view);
+
+    assert_eq!(clusters.len(), 1);
+    assert_eq!(
+        clusters[0].cells,
+        [(1, 0).into(), (0, 1).into(), (1, 1).into()] as [Position; 3]
+    );
+    // as a search deciding the cells from the last one, trying a mine first, finds them
+    assert_eq!(
+        clusters[0].solutions,
+        [
+            [false, false, true],
+            [false, true, false],
+            [true, false, false]
+        ]
+    );
+}
+
+#[test]
+fn skip_clusters_over_the_search_budget() {
+    let game: MineField = "*1  1*.\n11  2..\n    1*.\n".parse().unwrap();
+
+    // a single cell takes 2 steps to decide, as it can only hold a mine, but the 3 cells on the right take more
+    let clusters = solve_clusters_within(&game.view(), 4);
+    assert_eq!(clusters.len(), 1);
+    assert_eq!(clusters[0].cells, [(0, 0).into()] as [Position; 1]);
+
+    // a search done on its last step is within the budget
+    let clusters = solve_clusters_within(&game.view(), 2);
+    assert_eq!(clusters.len(), 1);
+    let clusters = solve_clusters_within(&game.view(), 1);
+    assert!(clusters.is_empty());
+
+    let clusters = solve_all_clusters(&game.view());
+    assert_eq!(clusters.len(), 2);
+    assert_eq!(clusters[1].solutions, [[true, false, true]]);
+}
+
+#[test]
+fn open_leaves_room_for_the_mines_left() {
+    let view = PlayerView::from_text("2.\n..\n", 2).unwrap();
+    let mut shadow_minefield = ShadowMinefield::new(&view);
+    let cell = |pos: (usize, usize)| ClusterCell {
+        pos: pos.into(),
+        open_neighbours: vec![(0, 0).into()],
+    };
+
+    // the 2 needs 2 of its 3 closed neighbours, so only one of them can be opened
+    assert!(shadow_minefield.open(&cell((1, 0))));
+    assert!(!shadow_minefield.open(&cell((0, 1))));
+    shadow_minefield.unopen(&cell((1, 0)));
+    assert!(shadow_minefield.open(&cell((0, 1))));
+}
+
+#[test]
+fn cluster_along_a_long_frontier() {
+    const WIDTH: usize = 50_000;
+    let text = format!("{}\n{}\n", ".".repeat(WIDTH), "1".repeat(WIDTH));
+    let view = PlayerView::from_text(&text, WIDTH / 3).unwrap();
+    let mut shadow_minefield = ShadowMinefield::new(&view);
+
+    let cluster = Cluster::from(&mut shadow_minefield, (0, 1).into());
+    assert_eq!(cluster.closed_positions.len(), WIDTH);
+}
+
+#[test]
+fn describe_a_single_arrangement() {
+    let cells: [Position; 3] = [(0, 1).into(), (1, 1).into(), (2, 1).into()];
+
+    assert_eq!(
+        describe_arrangements(&cells, &[[true, false, true]]),
+        "1 arrangement of 3 cells, mines on {(0, 1), (2, 1)}"
+    );
+    assert_eq!(
+        describe_arrangements(&cells[..1], &[[true], [false]]),
+        "2 arrangements of 1 cell, mines on {(0, 1)} or {}"
+    );
+}
@@ -1,11 +1,39 @@
-use crate::{MineField, Position};
+use crate::{Move, PlayerView};
 
+mod counting;
+mod frontier;
+mod hint;
 mod immediate;
 mod invariant;
+mod multi_cluster;
+mod probability;
 mod random;
+mod strategy;
 
-pub fn find_next_move(game: &MineField) -> Option<(Position, bool)> {
-    immediate::find_immediate_move(game)
-        .or_else(|| invariant::find_invariant_move(game))
-        .or_else(|| random::choose_random_move(game))
+pub use counting::MineCountStrategy;
+pub use frontier::Frontier;
+pub use hint::Hint;
+pub use immediate::ImmediateStrategy;
+pub use invariant::InvariantStrategy;
+pub use multi_cluster::MultiClusterStrategy;
+pub use probability::{ProbabilisticStrategy, ProbabilityMap};
+pub use random::RandomStrategy;
+pub use strategy::{Strategy, StrategyChain};
+
+/// `count` followed by `noun`, plural unless there is exactly one, for the reasons given along with moves
+fn counted(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("{count} {noun}")
+    } else {
+        format!("{count} {noun}s")
+    }
+}
+
+pub fn find_next_move(game: &PlayerView) -> Option<Move> {
+    StrategyChain::standard().next_move(game)
+}
+
+/// what the standard pipeline would play next, and why
+pub fn find_hint(game: &PlayerView) -> Option<Hint> {
+    StrategyChain::standard().hint(game)
 }
@@ -0,0 +1,156 @@
+use super::{
+    Frontier, Strategy,
+    counting::{decide_cluster, decide_sea},
+    invariant::ClusterSolutions,
+};
+use crate::{Move, PlayerView};
+
+pub struct MultiClusterStrategy;
+
+impl Strategy for MultiClusterStrategy {
+    fn name(&self) -> &'static str {
+        "multi-cluster"
+    }
+
+    fn next_move(&self, game: &PlayerView) -> Option<Move> {
+        self.next_move_on(&Frontier::new(game))
+    }
+
+    fn next_move_on(&self, frontier: &Frontier) -> Option<Move> {
+        find_multi_cluster_move(frontier)
+    }
+}
+
+fn count_mines(solution: &[bool]) -> usize {
+    solution.iter().filter(|mine| **mine).count()
+}
+
+/// `sums[t]` tells whether the clusters can hold `t` mines together
+fn achievable_sums<'a>(clusters: impl Iterator<Item = &'a ClusterSolutions>) -> Vec<bool> {
+    let mut sums = vec![true];
+
+    for cluster in clusters {
+        let mut next = vec![false; sums.len() + cluster.cells.len()];
+
+        for solution in &cluster.solutions {
+            let mines = count_mines(solution);
+            for (total, _) in sums.iter().enumerate().filter(|(_, possible)| **possible) {
+                next[total + mines] = true;
+            }
+        }
+
+        sums = next;
+    }
+
+    sums
+}
+
+/// whether `mines` on the frontier leave a number of mines the sea can hold
+fn fits(mines: usize, remaining_mines: usize, sea_size: usize) -> bool {
+    mines <= remaining_mines && remaining_mines - mines <= sea_size
+}
+
+/// combine the solutions of every cluster along the frontier: a solution of one cluster is only possible if the
+/// other clusters can hold a number of mines that, together with it, leaves what the sea can hold
+/// cells which are mined (or safe) in every remaining solution are decided, and so is the sea when every possible
+/// combination leaves it empty (or full)
+pub(super) fn find_multi_cluster_move(frontier: &Frontier) -> Option<Move> {
+    let remaining_mines = usize::try_from(frontier.game().remaining_mines()).ok()?;
+    let clusters = frontier.clusters();
+    let sea = frontier.sea();
+    let mines_left = format!("the {remaining_mines} mines left to all clusters");
+
+    for (index, cluster) in clusters.iter().enumerate() {
+        let others = achievable_sums(
+            clusters
+                .iter()
+                .enumerate()
+                .filter(|(other, _)| *other != index)
+                .map(|(_, other)| other),
+        );
+
+        let solutions: Vec<&Vec<bool>> = cluster
+            .solutions
+            .iter()
+            .filter(|solution| {
+                let mines = count_mines(solution);
+                others.iter().enumerate().any(|(other_mines, possible)| {
+                    *possible && fits(mines + other_mines, remaining_mines, sea.len())
+                })
+            })
+            .collect();
+
+        if solutions.is_empty() {
+            continue;
+        }
+
+        if let Some(next_move) = decide_cluster(cluster, &solutions, &mines_left) {
+            return Some(next_move);
+        }
+    }
+
+    let leftovers: Vec<usize> = achievable_sums(clusters.iter())
+        .into_iter()
+        .enumerate()
+        .filter(|(mines, possible)| *possible && fits(*mines, remaining_mines, sea.len()))
+        .map(|(mines, _)| remaining_mines - mines)
+        .collect();
+
+    decide_sea(&sea, &leftovers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Action, CellConfig, MineField};
+
+    #[test]
+    fn test_clusters_share_the_mines_left() {
+        let game = MineField::from([[
+            CellConfig::Closed,
+            CellConfig::Open,
+            CellConfig::Mine,
+            CellConfig::Open,
+            CellConfig::Closed,
+            CellConfig::Flagged,
+            CellConfig::Closed,
+            CellConfig::Open,
+            CellConfig::Mine,
+            CellConfig::Open,
+            CellConfig::Closed,
+        ]]);
+
+        let next_move = MultiClusterStrategy
+            .next_move(&game.view())
+            .map(|next_move| next_move.action);
+
+        match next_move {
+            None => panic!("Multi cluster solver should find a move for two 11 sharing 2 mines"),
+            Some(Action::Open(pos)) if [0, 4, 6, 10].contains(&pos.to_index(11)) => {}
+            Some(Action::Flag(pos)) if [2, 8].contains(&pos.to_index(11)) => {}
+            _ => panic!("Invalid move"),
+        }
+    }
+
+    #[test]
+    fn test_clusters_leave_no_mine_to_the_sea() {
+        let game = MineField::from([[
+            CellConfig::Closed,
+            CellConfig::Open,
+            CellConfig::Mine,
+            CellConfig::Flagged,
+            CellConfig::Mine,
+            CellConfig::Open,
+            CellConfig::Closed,
+            CellConfig::Flagged,
+            CellConfig::Closed,
+            CellConfig::Closed,
+        ]]);
+
+        let next_move = MultiClusterStrategy
+            .next_move(&game.view())
+            .map(|next_move| next_move.action);
+
+        assert_eq!(next_move, Some(Action::Open((8, 0).into())));
+    }
+}
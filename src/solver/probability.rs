@@ -0,0 +1,281 @@
+use super::{Frontier, Strategy, invariant::ClusterSolutions, random::choose_random_move};
+use crate::{Action, Grid, Move, PlayerView, Position};
+
+pub struct ProbabilisticStrategy;
+
+impl Strategy for ProbabilisticStrategy {
+    fn name(&self) -> &'static str {
+        "probability"
+    }
+
+    fn is_deterministic(&self) -> bool {
+        false
+    }
+
+    fn next_move(&self, game: &PlayerView) -> Option<Move> {
+        self.next_move_on(&Frontier::new(game))
+    }
+
+    /// guesses at random when the probabilities cannot be worked out, so that closed cells are never left unplayed
+    fn next_move_on(&self, frontier: &Frontier) -> Option<Move> {
+        let game = frontier.game();
+        let Some(map) = ProbabilityMap::on(frontier) else {
+            log::debug!(strategy = "probability"; "No probabilities, falling back to a random guess");
+            return choose_random_move(game, 0);
+        };
+        let (pos, probability) = map.safest()?;
+        Some(Move::new(Action::Open(pos)).with_reason(format!(
+            "guessing, {:.1}% chance of a mine",
+            probability * 100.
+        )))
+    }
+}
+
+/// chance for each closed cell to hold a mine, every arrangement of the remaining mines consistent with what the
+/// player sees being equally likely
+pub struct ProbabilityMap {
+    grid: Grid,
+    probabilities: Vec<Option<f64>>,
+}
+
+/// polynomial in the number of mines, `terms[k]` weighting arrangements with `k` mines
+type Weights = Vec<f64>;
+
+fn multiply(a: &[f64], b: &[f64]) -> Weights {
+    let mut product = vec![0.; a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            product[i + j] += x * y;
+        }
+    }
+    product
+}
+
+/// per cluster, the share of its solutions with `k` mines, and for each of its cells the share of its solutions
+/// with `k` mines where that cell is mined
+fn cluster_weights(cluster: &ClusterSolutions) -> (Weights, Vec<Weights>) {
+    let size = cluster.solutions.len() as f64;
+    let mut counts = vec![0.; cluster.cells.len() + 1];
+    let mut cell_counts = vec![vec![0.; cluster.cells.len() + 1]; cluster.cells.len()];
+
+    for solution in &cluster.solutions {
+        let mines = solution.iter().filter(|mine| **mine).count();
+        counts[mines] += 1. / size;
+        for (index, mine) in solution.iter().enumerate() {
+            if *mine {
+                cell_counts[index][mines] += 1. / size;
+            }
+        }
+    }
+
+    (counts, cell_counts)
+}
+
+impl ProbabilityMap {
+    /// `None` when the visible board is inconsistent, e.g. when more flags than mines were placed
+    ///
+    /// the cells of a cluster with too many arrangements to enumerate are weighed as cells out of reach of the numbers
+    pub fn new(game: &PlayerView) -> Option<Self> {
+        Self::on(&Frontier::new(game))
+    }
+
+    /// see [`ProbabilityMap::new`], with the clusters `frontier` already solved
+    pub fn on(frontier: &Frontier) -> Option<Self> {
+        let game = frontier.game();
+        let remaining_mines = usize::try_from(game.remaining_mines()).ok()?;
+        let clusters = frontier.clusters();
+        if clusters.iter().any(|cluster| cluster.solutions.is_empty()) {
+            return None;
+        }
+
+        let closed = game
+            .grid()
+            .positions()
+            .filter(|pos| game.cell_data(*pos).is_closed())
+            .count();
+        let sea = frontier.sea();
+
+        // relative number of ways to spread `remaining_mines - t` mines in the sea, for `t` mines on the frontier,
+        // kept in log space as the binomials outgrow floats on large boards
+        let mut log_factorials = vec![0.; sea.len() + 1];
+        for n in 1..=sea.len() {
+            log_factorials[n] = log_factorials[n - 1] + (n as f64).ln();
+        }
+        let log_sea_weights: Vec<Option<f64>> = (0..=closed)
+            .map(|frontier_mines| {
+                let sea_mines = remaining_mines.checked_sub(frontier_mines)?;
+                let sea_free = sea.len().checked_sub(sea_mines)?;
+                Some(
+                    log_factorials[sea.len()]
+                        - log_factorials[sea_mines]
+                        - log_factorials[sea_free],
+                )
+            })
+            .collect();
+        let max = log_sea_weights
+            .iter()
+            .flatten()
+            .copied()
+            .fold(f64::NEG_INFINITY, f64::max);
+        let sea_weights: Weights = log_sea_weights
+            .into_iter()
+            .map(|weight| weight.map_or(0., |weight| (weight - max).exp()))
+            .collect();
+        let sea_weight =
+            |frontier_mines: usize| sea_weights.get(frontier_mines).copied().unwrap_or(0.);
+
+        let weights: Vec<(Weights, Vec<Weights>)> = clusters.iter().map(cluster_weights).collect();
+        let frontier = weights
+            .iter()
+            .fold(vec![1.], |product, (counts, _)| multiply(&product, counts));
+
+        let total: f64 = frontier
+            .iter()
+            .enumerate()
+            .map(|(mines, weight)| weight * sea_weight(mines))
+            .sum();
+        if total <= 0. {
+            return None;
+        }
+
+        let mut probabilities = vec![None; game.grid().len()];
+
+        if !sea.is_empty() {
+            let sea_mines: f64 = frontier
+                .iter()
+                .enumerate()
+                .filter(|(mines, _)| *mines <= remaining_mines)
+                .map(|(mines, weight)| {
+                    weight * sea_weight(mines) * (remaining_mines - mines) as f64
+                })
+                .sum();
+            let probability = sea_mines / total / sea.len() as f64;
+            for pos in &sea {
+                probabilities[game.grid().index(*pos)] = Some(probability);
+            }
+        }
+
+        for (index, cluster) in clusters.iter().enumerate() {
+            let others = weights
+                .iter()
+                .enumerate()
+                .filter(|(other, _)| *other != index)
+                .fold(vec![1.], |product, (_, (counts, _))| {
+                    multiply(&product, counts)
+                });
+
+            for (pos, cell_counts) in cluster.cells.iter().zip(&weights[index].1) {
+                let mined: f64 = cell_counts
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(mines, weight)| {
+                        others.iter().enumerate().map(move |(other_mines, other)| {
+                            weight * other * sea_weight(mines + other_mines)
+                        })
+                    })
+                    .sum();
+                probabilities[game.grid().index(*pos)] = Some(mined / total);
+            }
+        }
+
+        Some(Self {
+            grid: game.grid().clone(),
+            probabilities,
+        })
+    }
+
+    /// `None` for cells which are not closed
+    pub fn get(&self, pos: Position) -> Option<f64> {
+        self.probabilities
+            .get(self.grid.index(pos))
+            .copied()
+            .flatten()
+    }
+
+    /// closed cell least likely to hold a mine, along with that probability
+    pub fn safest(&self) -> Option<(Position, f64)> {
+        self.probabilities
+            .iter()
+            .enumerate()
+            .filter_map(|(index, probability)| {
+                probability.map(|probability| (self.grid.position(index), probability))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CellConfig, MineField};
+
+    fn assert_close(probability: Option<f64>, expected: f64) {
+        let Some(probability) = probability else {
+            panic!("Expected a probability of {expected}, got none");
+        };
+        assert!(
+            (probability - expected).abs() < 1e-9,
+            "Expected a probability of {expected}, got {probability}"
+        );
+    }
+
+    #[test]
+    fn test_probabilities_weighted_by_sea() {
+        let game = MineField::from([[
+            CellConfig::Closed,
+            CellConfig::Open,
+            CellConfig::Mine,
+            CellConfig::Open,
+            CellConfig::Closed,
+            CellConfig::Mine,
+            CellConfig::Closed,
+            CellConfig::Closed,
+        ]]);
+
+        let map = ProbabilityMap::new(&game.view()).unwrap();
+
+        assert_close(map.get((0, 0).into()), 0.25);
+        assert_close(map.get((2, 0).into()), 0.75);
+        assert_close(map.get((4, 0).into()), 0.25);
+        assert_close(map.get((5, 0).into()), 0.25);
+        assert_close(map.get((7, 0).into()), 0.25);
+        assert_eq!(map.get((1, 0).into()), None);
+    }
+
+    #[test]
+    fn test_probabilistic_move_opens_safest_cell() {
+        let game = MineField::from([[
+            CellConfig::Closed,
+            CellConfig::Open,
+            CellConfig::Mine,
+            CellConfig::Closed,
+            CellConfig::Closed,
+            CellConfig::Closed,
+        ]]);
+
+        let next_move = ProbabilisticStrategy
+            .next_move(&game.view())
+            .map(|next_move| next_move.action);
+
+        match next_move {
+            Some(Action::Open(pos)) if pos.to_index(6) >= 3 => {}
+            _ => panic!("Invalid move"),
+        }
+    }
+
+    #[test]
+    fn test_probabilistic_move_without_probabilities() {
+        // more flags than mines, which no arrangement of mines agrees with
+        let view = PlayerView::from_text("FF..\n", 1).unwrap();
+        assert!(ProbabilityMap::new(&view).is_none());
+
+        let next_move = ProbabilisticStrategy
+            .next_move(&view)
+            .map(|next_move| next_move.action);
+
+        match next_move {
+            Some(Action::Open(pos)) if pos.x() >= 2 => {}
+            _ => panic!("Invalid move"),
+        }
+    }
+}
@@ -1,31 +1,57 @@
-use rand::seq::IndexedRandom;
+use std::hash::{DefaultHasher, Hash, Hasher};
 
-use crate::{CellData, MineField, Position};
+use rand::{SeedableRng, rngs::StdRng, seq::IndexedRandom};
 
-/// choose a random closed cell and open it
-pub(super) fn choose_random_move(game: &MineField) -> Option<(Position, bool)> {
-    let mut closed_positions = Vec::new();
+use super::Strategy;
+use crate::{Action, Move, PlayerView};
+
+/// open a closed cell at random, drawn from `seed` and the closed cells so that a game can be played again
+#[derive(Default)]
+pub struct RandomStrategy {
+    seed: u64,
+}
+
+impl RandomStrategy {
+    pub fn seeded(seed: u64) -> Self {
+        Self { seed }
+    }
+}
 
-    for y in 0..game.height() {
-        for x in 0..game.height() {
-            let pos = (x, y).into();
+impl Strategy for RandomStrategy {
+    fn name(&self) -> &'static str {
+        "random"
+    }
+
+    fn is_deterministic(&self) -> bool {
+        false
+    }
+
+    fn next_move(&self, game: &PlayerView) -> Option<Move> {
+        choose_random_move(game, self.seed)
+    }
+}
 
-            match game.cell_data(pos) {
-                CellData::Closed => {
-                    closed_positions.push(pos);
-                }
-                _ => {}
-            }
+/// choose a random closed cell and open it, drawn from `seed` and the closed cells
+pub(super) fn choose_random_move(game: &PlayerView, seed: u64) -> Option<Move> {
+    let mut closed_positions = Vec::new();
+    let mut hasher = DefaultHasher::new();
+    seed.hash(&mut hasher);
+
+    for pos in game.grid().positions() {
+        if game.cell_data(pos).is_closed() {
+            closed_positions.push(pos);
+            game.grid().index(pos).hash(&mut hasher);
         }
     }
 
-    let Some(pos) = closed_positions.choose(&mut rand::rng()) else {
-        println!("Could not find move to make!");
+    let mut rng = StdRng::seed_from_u64(hasher.finish());
+    let Some(pos) = closed_positions.choose(&mut rng) else {
+        log::debug!(strategy = "random"; "Could not find move to make!");
         return None;
     };
 
-    println!("Opening randomly {pos}");
-    Some((*pos, false))
+    log::debug!(strategy = "random", candidates = closed_positions.len(); "Opening randomly {pos}");
+    Some(Move::new(Action::Open(*pos)).with_reason("guessing at random"))
 }
 
 #[cfg(test)]
@@ -41,15 +67,43 @@ mod tests {
             [CellConfig::Closed, CellConfig::Open, CellConfig::Closed],
         ]);
 
-        let next_move = choose_random_move(&game);
+        let next_move = choose_random_move(&game.view(), 0).map(|next_move| next_move.action);
 
         match next_move {
             None => panic!("Random move should find a move when there are closed cells"),
-            Some((pos, false)) if game.cell_data(pos).is_closed() => {}
+            Some(Action::Open(pos)) if game.cell_data(pos).is_closed() => {}
             _ => panic!("Invalid move"),
         }
     }
 
+    #[test]
+    fn test_random_move_is_reproducible() {
+        let game = MineField::seeded(16, 16, 40, 3);
+        let moves = |seed| {
+            (0..10)
+                .map(|_| {
+                    RandomStrategy::seeded(seed)
+                        .next_move(&game.view())
+                        .unwrap()
+                        .action
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(moves(1), moves(1));
+        assert!((2..10).any(|seed| moves(seed)[0] != moves(1)[0]));
+    }
+
+    #[test]
+    fn test_random_move_skips_holes() {
+        let game: MineField = "#1*\n#11\n".parse().unwrap();
+
+        for _ in 0..10 {
+            let next_move = choose_random_move(&game.view(), 0).map(|next_move| next_move.action);
+            assert_eq!(next_move, Some(Action::Open((2, 0).into())));
+        }
+    }
+
     #[test]
     fn test_random_move_without_closed_cells() {
         let game = MineField::from([
@@ -58,7 +112,7 @@ mod tests {
             [CellConfig::Open, CellConfig::Open, CellConfig::Open],
         ]);
 
-        let next_move = choose_random_move(&game);
+        let next_move = choose_random_move(&game.view(), 0).map(|next_move| next_move.action);
 
         assert_eq!(next_move, None)
     }
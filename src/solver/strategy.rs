@@ -0,0 +1,227 @@
+use std::{str::FromStr, time::Instant};
+
+use super::Frontier;
+use crate::{Move, PlayerView};
+
+/// one stage of the solver pipeline
+pub trait Strategy {
+    fn name(&self) -> &'static str;
+
+    /// whether the moves of this strategy are certain, rather than guesses
+    fn is_deterministic(&self) -> bool {
+        true
+    }
+
+    /// next move to play on `game`, or `None` when this strategy cannot decide on one
+    fn next_move(&self, game: &PlayerView) -> Option<Move>;
+
+    /// next move to play on the board of `frontier`, reusing what the stages before this one in a chain worked out
+    fn next_move_on(&self, frontier: &Frontier) -> Option<Move> {
+        self.next_move(frontier.game())
+    }
+}
+
+/// strategies tried in order until one of them finds a move
+#[derive(Default)]
+pub struct StrategyChain {
+    strategies: Vec<Box<dyn Strategy>>,
+}
+
+impl StrategyChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the pipeline used by [`crate::find_next_move`]: immediate, invariant, counting, multi-cluster, and when none
+    /// of them can decide, a guess on the cell least likely to hold a mine, or on any closed cell when the odds cannot
+    /// be worked out
+    pub fn standard() -> Self {
+        Self::deterministic().with(super::ProbabilisticStrategy)
+    }
+
+    /// the stages of [`StrategyChain::standard`] which never guess
+    pub fn deterministic() -> Self {
+        Self::new()
+            .with(super::ImmediateStrategy)
+            .with(super::InvariantStrategy)
+            .with(super::MineCountStrategy)
+            .with(super::MultiClusterStrategy)
+    }
+
+    /// append a strategy at the end of the chain
+    pub fn with(mut self, strategy: impl Strategy + 'static) -> Self {
+        self.push(strategy);
+        self
+    }
+
+    pub fn push(&mut self, strategy: impl Strategy + 'static) {
+        self.strategies.push(Box::new(strategy));
+    }
+
+    /// insert a strategy so that it is tried at `index`, shifting the following ones
+    pub fn insert(&mut self, index: usize, strategy: impl Strategy + 'static) {
+        self.strategies.insert(index, Box::new(strategy));
+    }
+
+    /// remove the strategy with the given name, returning it if it was in the chain
+    pub fn remove(&mut self, name: &str) -> Option<Box<dyn Strategy>> {
+        let index = self.position(name)?;
+        Some(self.strategies.remove(index))
+    }
+
+    /// put a strategy in place of the one with the given name, returning the replaced one
+    pub fn replace(
+        &mut self,
+        name: &str,
+        strategy: impl Strategy + 'static,
+    ) -> Option<Box<dyn Strategy>> {
+        let index = self.position(name)?;
+        Some(std::mem::replace(
+            &mut self.strategies[index],
+            Box::new(strategy),
+        ))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Strategy> {
+        self.strategies.iter().map(|strategy| strategy.as_ref())
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.strategies
+            .iter()
+            .map(|strategy| strategy.name())
+            .collect()
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.strategies
+            .iter()
+            .position(|strategy| strategy.name() == name)
+    }
+}
+
+/// comma separated names of strategies, e.g. `immediate,invariant,random`
+impl FromStr for StrategyChain {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chain = Self::new();
+
+        for name in s.split(',').map(str::trim) {
+            match name {
+                "immediate" => chain.push(super::ImmediateStrategy),
+                "invariant" => chain.push(super::InvariantStrategy),
+                "counting" => chain.push(super::MineCountStrategy),
+                "multi-cluster" => chain.push(super::MultiClusterStrategy),
+                "probability" => chain.push(super::ProbabilisticStrategy),
+                "random" => chain.push(super::RandomStrategy::default()),
+                _ => return Err(format!("Unknown strategy {name:?}")),
+            }
+        }
+
+        Ok(chain)
+    }
+}
+
+impl Strategy for StrategyChain {
+    fn name(&self) -> &'static str {
+        "chain"
+    }
+
+    fn is_deterministic(&self) -> bool {
+        self.strategies
+            .iter()
+            .all(|strategy| strategy.is_deterministic())
+    }
+
+    fn next_move(&self, game: &PlayerView) -> Option<Move> {
+        self.next_move_on(&Frontier::new(game))
+    }
+
+    /// moves are tagged with the name of the strategy which found them, unless it already did
+    fn next_move_on(&self, frontier: &Frontier) -> Option<Move> {
+        self.strategies.iter().find_map(|strategy| {
+            let start = Instant::now();
+            let next_move = strategy.next_move_on(frontier);
+            let elapsed_us = start.elapsed().as_micros() as u64;
+
+            let Some(mut next_move) = next_move else {
+                log::trace!(strategy = strategy.name(), elapsed_us; "[{}] No move", strategy.name());
+                return None;
+            };
+
+            next_move.strategy.get_or_insert(strategy.name());
+            log::debug!(
+                strategy = strategy.name(),
+                action:% = next_move.action,
+                elapsed_us;
+                "[{}] Playing {}", strategy.name(), next_move.action
+            );
+            Some(next_move)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Action, CellConfig, ImmediateStrategy, InvariantStrategy, MineField, RandomStrategy,
+    };
+
+    struct Fixed(Action);
+
+    impl Strategy for Fixed {
+        fn name(&self) -> &'static str {
+            "fixed"
+        }
+
+        fn next_move(&self, _game: &PlayerView) -> Option<Move> {
+            Some(Move::new(self.0.clone()))
+        }
+    }
+
+    #[test]
+    fn test_chain_editing() {
+        let mut chain = StrategyChain::new()
+            .with(ImmediateStrategy)
+            .with(InvariantStrategy)
+            .with(RandomStrategy::default());
+        assert_eq!(chain.names(), ["immediate", "invariant", "random"]);
+
+        chain.insert(1, Fixed(Action::Open((0, 0).into())));
+        assert_eq!(chain.names(), ["immediate", "fixed", "invariant", "random"]);
+
+        assert!(chain.remove("invariant").is_some());
+        assert!(chain.remove("invariant").is_none());
+        assert!(chain.replace("random", InvariantStrategy).is_some());
+        assert_eq!(chain.names(), ["immediate", "fixed", "invariant"]);
+    }
+
+    #[test]
+    fn test_chain_from_names() {
+        let chain: StrategyChain = "immediate, multi-cluster,random".parse().unwrap();
+        assert_eq!(chain.names(), ["immediate", "multi-cluster", "random"]);
+
+        assert!("immediate,guessing".parse::<StrategyChain>().is_err());
+    }
+
+    #[test]
+    fn test_chain_tries_strategies_in_order() {
+        let game = MineField::from([[CellConfig::Open, CellConfig::Mine, CellConfig::Closed]]);
+
+        let chain = StrategyChain::new()
+            .with(ImmediateStrategy)
+            .with(Fixed(Action::Open((2, 0).into())));
+        let next_move = chain.next_move(&game.view()).unwrap();
+        assert_eq!(next_move.action, Action::Flag((1, 0).into()));
+        assert_eq!(next_move.strategy, Some("immediate"));
+
+        let chain = StrategyChain::new()
+            .with(Fixed(Action::Open((2, 0).into())))
+            .with(RandomStrategy::default());
+        let next_move = chain.next_move(&game.view()).unwrap();
+        assert_eq!(next_move.action, Action::Open((2, 0).into()));
+        assert_eq!(next_move.strategy, Some("fixed"));
+    }
+}
@@ -0,0 +1,359 @@
+use std::{
+    io::{self, Write},
+    time::{Duration, Instant},
+};
+
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute, queue,
+    style::{Attribute, Print, SetAttribute},
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+
+use crate::{
+    Action, CellData, GameState, MineField, Position, Tiling, find_hint,
+    replay::{Recorder, Replay},
+};
+
+const HELP: &str = "arrows/hjkl move  </> layer  space open  f flag  ? mark  c chord  i hint  u undo  r redo  n new game  q quit";
+
+/// lines of the screen which do not show the field: the status line above it, the help line below it
+const RESERVED_LINES: usize = 2;
+
+/// play `game` by hand in a full screen terminal interface until the player quits, returning the field as it was left
+/// and the replay of the game
+pub fn play(game: MineField) -> io::Result<(MineField, Replay)> {
+    let mut stdout = io::stdout();
+
+    let screen = FullScreen::enter(&mut stdout)?;
+
+    let mut tui = Tui::new(game);
+    let result = tui.run(&mut stdout);
+
+    screen.leave()?;
+
+    result.map(|()| {
+        let replay = tui.recorder.finish(&tui.game);
+        (tui.game, replay)
+    })
+}
+
+/// the terminal in raw mode on the alternate screen, given back to the shell as it was when dropped, so that a panic
+/// does not leave it unusable
+struct FullScreen {
+    active: bool,
+}
+
+impl FullScreen {
+    fn enter(out: &mut impl Write) -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        let screen = Self { active: true };
+        execute!(out, EnterAlternateScreen, Hide)?;
+        Ok(screen)
+    }
+
+    /// restore the terminal, reporting what failed to, unlike dropping
+    fn leave(mut self) -> io::Result<()> {
+        self.active = false;
+        let left = execute!(io::stdout(), Show, LeaveAlternateScreen);
+        terminal::disable_raw_mode()?;
+        left
+    }
+}
+
+impl Drop for FullScreen {
+    fn drop(&mut self) {
+        if self.active {
+            let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+            let _ = terminal::disable_raw_mode();
+        }
+    }
+}
+
+struct Tui {
+    game: MineField,
+    cursor: Position,
+    /// top left cell of the part of the field on screen
+    offset: Position,
+    started: Option<Instant>,
+    finished: Option<Duration>,
+    message: Option<String>,
+    recorder: Recorder,
+}
+
+impl Tui {
+    fn new(game: MineField) -> Self {
+        Self {
+            cursor: game.grid().centre().unwrap_or((0, 0).into()),
+            game,
+            offset: (0, 0).into(),
+            started: None,
+            finished: None,
+            message: None,
+            recorder: Recorder::new(),
+        }
+    }
+
+    fn run(&mut self, out: &mut impl Write) -> io::Result<()> {
+        execute!(out, Clear(ClearType::All))?;
+
+        loop {
+            self.draw(out)?;
+
+            // wake up every now and then so that the timer keeps ticking
+            if !event::poll(Duration::from_millis(200))? {
+                continue;
+            }
+
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press && !self.handle_key(key) => {
+                    return Ok(());
+                }
+                Event::Resize(_, _) => execute!(out, Clear(ClearType::All))?,
+                _ => {}
+            }
+        }
+    }
+
+    /// returns false when the player quits
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        self.message = None;
+
+        let action = match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return false,
+            KeyCode::Left | KeyCode::Char('h') => self.move_cursor(-1, 0, 0),
+            KeyCode::Right | KeyCode::Char('l') => self.move_cursor(1, 0, 0),
+            KeyCode::Up | KeyCode::Char('k') => self.move_cursor(0, -1, 0),
+            KeyCode::Down | KeyCode::Char('j') => self.move_cursor(0, 1, 0),
+            KeyCode::PageUp | KeyCode::Char('<') => self.move_cursor(0, 0, -1),
+            KeyCode::PageDown | KeyCode::Char('>') => self.move_cursor(0, 0, 1),
+            KeyCode::Char('n') => self.restart(),
+            KeyCode::Char('i') => self.hint(),
+            KeyCode::Char('u') => self.take_back(MineField::undo),
+            KeyCode::Char('r') => self.take_back(MineField::redo),
+            // opening a number is the usual shortcut for chording on it, and a flag guards its cell from being opened
+            KeyCode::Char(' ') | KeyCode::Enter => match self.game.cell_data(self.cursor) {
+                CellData::Open(_) => Some(Action::Chord(self.cursor)),
+                CellData::Flagged => None,
+                _ => Some(Action::Open(self.cursor)),
+            },
+            KeyCode::Char('f') => Some(Action::ToggleFlag(self.cursor)),
+            KeyCode::Char('?') | KeyCode::Char('m') => Some(Action::QuestionMark(self.cursor)),
+            KeyCode::Char('c') => Some(Action::Chord(self.cursor)),
+            _ => None,
+        };
+
+        if let Some(action) = action {
+            self.play(action);
+        }
+
+        true
+    }
+
+    fn play(&mut self, action: Action) {
+        match self.recorder.play(&mut self.game, action) {
+            Ok(state) => {
+                let started = *self.started.get_or_insert_with(Instant::now);
+                if state != GameState::Ongoing {
+                    self.finished = Some(started.elapsed());
+                }
+            }
+            Err(error) => self.message = Some(error.to_string()),
+        }
+    }
+
+    /// move the cursor within its layer, or onto the layer above or below it, jumping over holes
+    fn move_cursor(&mut self, dx: isize, dy: isize, dz: isize) -> Option<Action> {
+        let mut pos = self.cursor;
+        while let (Some(x), Some(y), Some(z)) = (
+            pos.x().checked_add_signed(dx),
+            pos.y().checked_add_signed(dy),
+            pos.z().checked_add_signed(dz),
+        ) {
+            pos = (x, y, z).into();
+
+            if !self.game.grid().is_masked(pos) {
+                break;
+            }
+        }
+
+        if self.game.contains(pos) {
+            self.cursor = pos;
+        }
+
+        None
+    }
+
+    /// show what the solver would play and why, moving the cursor onto the cell it would play
+    fn hint(&mut self) -> Option<Action> {
+        match find_hint(&self.game.view()) {
+            Some(hint) => {
+                if let Some(pos) = hint.action.position() {
+                    self.cursor = pos;
+                }
+                self.message = Some(hint.to_string());
+            }
+            None => self.message = Some("No hint available".to_string()),
+        }
+
+        None
+    }
+
+    /// undo or redo an action, moving the cursor onto its cell
+    fn take_back(&mut self, step: fn(&mut MineField) -> Option<Action>) -> Option<Action> {
+        match step(&mut self.game) {
+            Some(action) => {
+                if let Some(pos) = action.position() {
+                    self.cursor = pos;
+                }
+                if self.game.state() == GameState::Ongoing {
+                    self.finished = None;
+                } else if let Some(started) = self.started {
+                    self.finished = Some(started.elapsed());
+                }
+            }
+            None => self.message = Some("Nothing to undo or redo".to_string()),
+        }
+
+        None
+    }
+
+    /// new board of the same shape and with as many mines
+    fn restart(&mut self) -> Option<Action> {
+        let game = MineField::seeded_on(
+            self.game.grid().clone(),
+            self.game.mines() as u32,
+            rand::random(),
+        );
+        *self = Self::new(game);
+        None
+    }
+
+    fn elapsed(&self) -> Duration {
+        match (self.started, self.finished) {
+            (_, Some(finished)) => finished,
+            (Some(started), None) => started.elapsed(),
+            (None, None) => Duration::ZERO,
+        }
+    }
+
+    fn face(&self) -> &'static str {
+        match self.game.state() {
+            GameState::Ongoing => ":)",
+            GameState::Won => "B)",
+            GameState::Lost => "X(",
+        }
+    }
+
+    fn draw(&mut self, out: &mut impl Write) -> io::Result<()> {
+        let (columns, rows) = terminal::size()?;
+        let visible_height = (rows as usize).saturating_sub(RESERVED_LINES).max(1);
+        // every cell is followed by a space so that the field looks square, and hexagonal rows are shifted by half a
+        // cell each
+        let hexagonal = self.game.grid().tiling() == Tiling::Hexagonal;
+        let indent = if hexagonal { visible_height } else { 0 };
+        let visible_width = ((columns as usize).saturating_sub(indent) / 2).max(1);
+
+        self.offset = (
+            scroll(
+                self.offset.x(),
+                self.cursor.x(),
+                visible_width,
+                self.game.width(),
+            ),
+            scroll(
+                self.offset.y(),
+                self.cursor.y(),
+                visible_height,
+                self.game.height(),
+            ),
+        )
+            .into();
+
+        // only the layer of the cursor is on screen
+        let depth = self.game.grid().depth();
+        let layer = if depth > 1 {
+            format!("   layer {}/{depth}", self.cursor.z() + 1)
+        } else {
+            String::new()
+        };
+
+        queue!(
+            out,
+            MoveTo(0, 0),
+            Print(format!(
+                "{:>4}   {}   {:>4}{layer}",
+                self.game.remaining_mines(),
+                self.face(),
+                self.elapsed().as_secs()
+            )),
+            Clear(ClearType::UntilNewLine)
+        )?;
+
+        let last_x = (self.offset.x() + visible_width).min(self.game.width());
+        let last_y = (self.offset.y() + visible_height).min(self.game.height());
+
+        for (line, y) in (self.offset.y()..last_y).enumerate() {
+            let indent = if hexagonal { line } else { 0 };
+            queue!(out, MoveTo(indent as u16, line as u16 + 1))?;
+
+            for x in self.offset.x()..last_x {
+                let pos = (x, y, self.cursor.z()).into();
+                let glyph = self.game.cell_glyph(pos);
+
+                if pos == self.cursor {
+                    queue!(
+                        out,
+                        SetAttribute(Attribute::Reverse),
+                        Print(glyph),
+                        SetAttribute(Attribute::Reset)
+                    )?;
+                } else {
+                    queue!(out, Print(glyph))?;
+                }
+                queue!(out, Print(" "))?;
+            }
+
+            queue!(out, Clear(ClearType::UntilNewLine))?;
+        }
+
+        queue!(
+            out,
+            MoveTo(0, (last_y - self.offset.y()) as u16 + 1),
+            Clear(ClearType::FromCursorDown),
+            Print(self.message.as_deref().unwrap_or(HELP))
+        )?;
+
+        out.flush()
+    }
+}
+
+/// first index of a window of `size` cells over an axis of `length` cells, moved as little as possible from `offset`
+/// so that the window contains `cursor`
+fn scroll(offset: usize, cursor: usize, size: usize, length: usize) -> usize {
+    let offset = if cursor < offset {
+        cursor
+    } else if cursor >= offset + size {
+        cursor + 1 - size
+    } else {
+        offset
+    };
+
+    offset.min(length.saturating_sub(size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scroll_follows_the_cursor() {
+        assert_eq!(scroll(0, 5, 10, 50), 0);
+        assert_eq!(scroll(0, 12, 10, 50), 3);
+        assert_eq!(scroll(20, 15, 10, 50), 15);
+        assert_eq!(scroll(45, 49, 10, 50), 40);
+        assert_eq!(scroll(3, 2, 10, 5), 0);
+    }
+}